@@ -0,0 +1,27 @@
+use hdk::prelude::*;
+use zome_utils::*;
+use zome_delivery_types::{ParcelChunk, ParcelManifest};
+
+
+/// Delete parcel manifests and chunks from my source-chain, given the ActionHashes of their Create.
+/// Lets other zomes delete parcels through the zome that owns their entry types.
+#[hdk_extern]
+pub fn delete_parcel_entries(ahs: Vec<ActionHash>) -> ExternResult<Vec<ActionHash>> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let me = agent_info()?.agent_latest_pubkey;
+    let mut res = Vec::new();
+    for ah in ahs {
+        let Some(record) = get(ah.clone(), GetOptions::default())?
+            else { return zome_error!("Parcel entry not found: {}", ah) };
+        if record.action().author() != &me {
+            return error("Only the author of a parcel entry can delete it");
+        }
+        let is_parcel = record.entry().to_app_option::<ParcelManifest>().ok().flatten().is_some()
+            || record.entry().to_app_option::<ParcelChunk>().ok().flatten().is_some();
+        if !is_parcel {
+            return zome_error!("Entry is not a parcel manifest or chunk: {}", ah);
+        }
+        res.push(delete_entry(ah)?);
+    }
+    Ok(res)
+}
@@ -1,3 +1,6 @@
 extern crate delivery;
 
 pub use delivery::*;
+
+mod delete_parcel;
+pub use delete_parcel::*;
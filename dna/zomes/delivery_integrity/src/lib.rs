@@ -1,3 +1,6 @@
 extern crate zome_delivery_integrity;
 
+/// Validation comes as-is from delivery-zome.
+/// It does not restrict who can delete a public ParcelManifest or a public parcel link:
+/// see unpublish_file() in the files zome.
 pub use zome_delivery_integrity::*;
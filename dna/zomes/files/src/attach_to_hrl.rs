@@ -1,12 +1,28 @@
 use hdk::prelude::*;
 use zome_utils::*;
+use zome_delivery_types::*;
 use zome_files_integrity::*;
 use crate::attach_to_hrl::holo_hash::DnaHash;
+use crate::utils::{ensure_parcel_is_file, get_links_batch, query_deleted_ahs};
+use crate::get_any_record::get_ah;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct AttachInput {
     hrl: (DnaHash, EntryHash),
     manifestEh: EntryHash,
+    label: Option<String>,
+}
+
+
+/// A File attached to a HRL
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AttachmentInfo {
+    pub manifest_eh: EntryHash,
+    pub author: AgentPubKey,
+    pub timestamp: Timestamp,
+    pub label: Option<String>,
+    pub description: ParcelDescription,
+    pub is_private: bool,
 }
 
 
@@ -14,45 +30,311 @@ pub struct AttachInput {
 #[hdk_extern]
 pub fn attach_to_hrl(input: AttachInput) -> ExternResult<ActionHash> {
     std::panic::set_hook(Box::new(zome_panic_hook));
+    /// Make sure manifest is a public File
+    let manifest: ParcelManifest = get_typed_from_eh(input.manifestEh.clone())?;
+    ensure_parcel_is_file(&manifest.description)?;
+    if manifest.description.visibility != EntryVisibility::Public {
+        return error("Can only attach a public File to a HRL");
+    }
+    let Some(manifest_ah) = get_ah(input.manifestEh.clone())?
+        else { return error("Manifest record not found") };
     /// Create Path
-    let tp = hrl_path(input.hrl)?;
+    let tp = hrl_path(input.hrl.clone())?;
     tp.ensure()?;
-    /// Create link
-    let ah = create_link(tp.path_entry_hash()?, input.manifestEh, FilesLinkTypes::Attachment, LinkTag::from(()))?;
+    /// Create links
+    let tag = AttachmentTag::new(input.hrl, manifest_ah, input.label).to_link_tag()?;
+    let ah = create_link(tp.path_entry_hash()?, input.manifestEh.clone(), FilesLinkTypes::Attachment, tag.clone())?;
+    let _ = create_link(input.manifestEh, tp.path_entry_hash()?, FilesLinkTypes::AttachedHrl, tag)?;
     /// Done
     Ok(ah)
 }
 
 
-/// Get File From a HRL link
+/// Attach a File to a We HRL without publishing anything.
+/// The attachment is stored as a private entry on my source-chain.
 #[hdk_extern]
-pub fn get_files_from_hrl(hrl: (DnaHash, EntryHash)) -> ExternResult<Vec<EntryHash>> {
+pub fn attach_to_hrl_privately(input: AttachInput) -> ExternResult<ActionHash> {
     std::panic::set_hook(Box::new(zome_panic_hook));
-    let tp = hrl_path(hrl)?;
-    /// Grab links
-    let links = get_links(tp.path_entry_hash()?, FilesLinkTypes::Attachment, None)?;
-    let res = links.into_iter()
-        .map(|link| link.target.into_entry_hash().unwrap())
+    /// Make sure manifest is a File
+    let manifest: ParcelManifest = get_typed_from_eh(input.manifestEh.clone())?;
+    ensure_parcel_is_file(&manifest.description)?;
+    /// Create Entry
+    let attachment = PrivateAttachment {
+        hrl: input.hrl,
+        manifest_eh: input.manifestEh,
+        label: input.label,
+    };
+    let ah = create_entry(FilesEntry::PrivateAttachment(attachment))?;
+    /// Done
+    Ok(ah)
+}
+
+
+/// Return all PrivateAttachments on my source-chain that have not been deleted
+pub fn query_private_attachments() -> ExternResult<Vec<(ActionHash, Timestamp, PrivateAttachment)>> {
+    let tuples = get_all_typed_local::<PrivateAttachment>(FilesEntryTypes::PrivateAttachment.try_into().unwrap())?;
+    let deleted_ahs = query_deleted_ahs()?;
+    let res = tuples.into_iter()
+        .filter(|(ah, _, _)| !deleted_ahs.contains(ah))
+        .map(|(ah, create, attachment)| (ah, create.timestamp, attachment))
         .collect();
+    Ok(res)
+}
+
+
+/// Delete the links between a We HRL and a File, in both directions, and my private attachments.
+/// Only links created by this agent can be deleted, unless the agent is the File's author.
+#[hdk_extern]
+pub fn detach_from_hrl(input: AttachInput) -> ExternResult<()> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let me = agent_info()?.agent_latest_pubkey;
+    /// Delete private attachments
+    let private_ahs: Vec<ActionHash> = query_private_attachments()?
+        .into_iter()
+        .filter(|(_, _, attachment)| attachment.hrl == input.hrl && attachment.manifest_eh == input.manifestEh)
+        .map(|(ah, _, _)| ah)
+        .collect();
+    let mut count = private_ahs.len();
+    for ah in private_ahs {
+        let _ = delete_entry(ah)?;
+    }
+    /// Grab links in both directions
+    let tp = hrl_path(input.hrl)?;
+    let hrl_eh = tp.path_entry_hash()?;
+    let mut links: Vec<Link> = get_links(hrl_eh.clone(), FilesLinkTypes::Attachment, None)?
+        .into_iter()
+        .filter(|link| link.target.clone().into_entry_hash() == Some(input.manifestEh.clone()))
+        .collect();
+    if links.is_empty() {
+        if count == 0 {
+            return error("File is not attached to HRL");
+        }
+        return Ok(());
+    }
+    let reverse_links: Vec<Link> = get_links(input.manifestEh.clone(), FilesLinkTypes::AttachedHrl, None)?
+        .into_iter()
+        .filter(|link| link.target.clone().into_entry_hash() == Some(hrl_eh.clone()))
+        .collect();
+    links.extend(reverse_links);
+    /// Check if I am the File's author
+    let is_manifest_author = match get(input.manifestEh, GetOptions::content())? {
+        Some(record) => record.action().author() == &me,
+        None => false,
+    };
+    /// Delete links
+    for link in links {
+        if link.author != me && !is_manifest_author {
+            continue;
+        }
+        let _ = delete_link(link.create_link_hash)?;
+        count += 1;
+    }
+    if count == 0 {
+        return error("Not allowed to detach File from HRL");
+    }
+    /// Done
+    Ok(())
+}
+
+
+/// Detach a File from every HRL it is attached to
+pub fn detach_from_all_hrls(manifest_eh: EntryHash) -> ExternResult<()> {
+    for hrl in get_hrls_for_file(manifest_eh.clone())? {
+        detach_from_hrl(AttachInput { hrl, manifestEh: manifest_eh.clone(), label: None })?;
+    }
+    Ok(())
+}
+
+
+/// Make attachments of a File follow to another File.
+/// Public attachments become private attachments if the other File is private.
+pub fn move_attachments(from_eh: EntryHash, to_eh: EntryHash, to_is_public: bool) -> ExternResult<()> {
+    /// Move private attachments
+    for (ah, _ts, attachment) in query_private_attachments()? {
+        if attachment.manifest_eh != from_eh {
+            continue;
+        }
+        let moved = PrivateAttachment { manifest_eh: to_eh.clone(), ..attachment };
+        let _ = create_entry(FilesEntry::PrivateAttachment(moved))?;
+        let _ = delete_entry(ah)?;
+    }
+    /// Move public attachments
+    let mut hrls = Vec::new();
+    for link in get_links(from_eh.clone(), FilesLinkTypes::AttachedHrl, None)? {
+        let Ok(tag) = AttachmentTag::from_link_tag(&link.tag)
+            else { continue };
+        if hrls.contains(&tag.hrl) {
+            continue;
+        }
+        hrls.push(tag.hrl.clone());
+        let input = AttachInput { hrl: tag.hrl.clone(), manifestEh: to_eh.clone(), label: tag.label };
+        if to_is_public {
+            let _ = attach_to_hrl(input)?;
+        } else {
+            let _ = attach_to_hrl_privately(input)?;
+        }
+        /// Links made by other agents can only be removed by them or by the File's author
+        let _ = detach_from_hrl(AttachInput { hrl: tag.hrl, manifestEh: from_eh.clone(), label: None });
+    }
     /// Done
+    Ok(())
+}
+
+
+/// Get Files attached to a HRL, publicly or privately by me
+#[hdk_extern]
+pub fn get_files_from_hrl(hrl: (DnaHash, EntryHash)) -> ExternResult<Vec<AttachmentInfo>> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let res = get_attachments(vec![hrl])?;
+    Ok(res.into_iter().next().map(|(_hrl, infos)| infos).unwrap_or_default())
+}
+
+
+/// Batch version of get_files_from_hrl().
+/// Return each HRL with its attachments.
+#[hdk_extern]
+pub fn get_files_from_hrls(hrls: Vec<(DnaHash, EntryHash)>) -> ExternResult<Vec<((DnaHash, EntryHash), Vec<AttachmentInfo>)>> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    get_attachments(hrls)
+}
+
+
+/// Count Files attached to each HRL, publicly or privately by me.
+/// Manifests are not fetched, so attachments whose manifest cannot be fetched are counted
+/// although get_files_from_hrls() skips them.
+#[hdk_extern]
+pub fn count_files_for_hrls(hrls: Vec<(DnaHash, EntryHash)>) -> ExternResult<Vec<((DnaHash, EntryHash), u32)>> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let res = resolve_attachments(hrls)?
+        .into_iter()
+        .map(|(hrl, attached)| (hrl, attached.len() as u32))
+        .collect();
     Ok(res)
 }
 
 
-///
-fn hrl_path(hrl: (DnaHash, EntryHash)) -> ExternResult<TypedPath> {
-    let mut tp = root_path()?;
-    let dnaComp = hash2comp(hrl.0);
-    let ehComp = hash2comp(hrl.1);
-    tp.path.append_component(dnaComp);
-    tp.path.append_component(ehComp);
-    Ok(tp)
+/// A File attached to a HRL, before its manifest is fetched
+struct AttachedFile {
+    manifest_eh: EntryHash,
+    author: AgentPubKey,
+    timestamp: Timestamp,
+    label: Option<String>,
+    is_private: bool,
+}
+
+
+/// Get Files attached to each HRL, publicly or privately by me.
+/// Attachments whose manifest cannot be fetched are skipped.
+fn get_attachments(hrls: Vec<(DnaHash, EntryHash)>) -> ExternResult<Vec<((DnaHash, EntryHash), Vec<AttachmentInfo>)>> {
+    /// Cache descriptions since a File can be attached to many HRLs
+    let mut descriptions: HashMap<EntryHash, Option<ParcelDescription>> = HashMap::new();
+    let mut res = Vec::new();
+    for (hrl, attached) in resolve_attachments(hrls)? {
+        let mut infos = Vec::new();
+        for file in attached {
+            let maybe_description = descriptions.entry(file.manifest_eh.clone())
+                .or_insert_with(|| get_typed_from_eh::<ParcelManifest>(file.manifest_eh.clone()).ok().map(|manifest| manifest.description))
+                .clone();
+            let Some(description) = maybe_description
+                else { debug!("Attached manifest not found: {}", file.manifest_eh); continue };
+            infos.push(AttachmentInfo {
+                manifest_eh: file.manifest_eh,
+                author: file.author,
+                timestamp: file.timestamp,
+                label: file.label,
+                description,
+                is_private: file.is_private,
+            });
+        }
+        res.push((hrl, infos));
+    }
+    Ok(res)
+}
+
+
+/// Get Files attached to each HRL, publicly or privately by me, without fetching their manifests.
+/// HRLs are sorted and de-duplicated.
+/// A File attached many times to the same HRL is listed once: my private attachment first, then the oldest link.
+/// All HRL paths are resolved with a single get_links() host call.
+fn resolve_attachments(mut hrls: Vec<(DnaHash, EntryHash)>) -> ExternResult<Vec<((DnaHash, EntryHash), Vec<AttachedFile>)>> {
+    hrls.sort();
+    hrls.dedup();
+    let me = agent_info()?.agent_latest_pubkey;
+    let private_attachments = query_private_attachments()?;
+    let bases = hrls.iter()
+        .map(|hrl| hrl_path(hrl.clone())?.path_entry_hash())
+        .collect::<ExternResult<Vec<EntryHash>>>()?;
+    let links_per_hrl = get_links_batch(bases, FilesLinkTypes::Attachment)?;
+    let mut res = Vec::new();
+    for (hrl, mut links) in hrls.into_iter().zip(links_per_hrl.into_iter()) {
+        let mut attached: Vec<AttachedFile> = Vec::new();
+        /// Grab my private attachments
+        for (_ah, ts, attachment) in private_attachments.iter() {
+            if attachment.hrl != hrl || attached.iter().any(|file| file.manifest_eh == attachment.manifest_eh) {
+                continue;
+            }
+            attached.push(AttachedFile {
+                manifest_eh: attachment.manifest_eh.clone(),
+                author: me.clone(),
+                timestamp: ts.to_owned(),
+                label: attachment.label.clone(),
+                is_private: true,
+            });
+        }
+        /// Grab links
+        links.sort_by_key(|link| link.timestamp);
+        for link in links {
+            let Some(manifest_eh) = link.target.into_entry_hash()
+                else { continue };
+            if attached.iter().any(|file| file.manifest_eh == manifest_eh) {
+                continue;
+            }
+            let label = AttachmentTag::from_link_tag(&link.tag)
+                .map(|tag| tag.label)
+                .unwrap_or(None);
+            attached.push(AttachedFile {
+                manifest_eh,
+                author: link.author,
+                timestamp: link.timestamp,
+                label,
+                is_private: false,
+            });
+        }
+        res.push((hrl, attached));
+    }
+    /// Done
+    Ok(res)
+}
+
+
+/// Get HRLs a File is attached to, publicly or privately by me
+#[hdk_extern]
+pub fn get_hrls_for_file(manifest_eh: EntryHash) -> ExternResult<Vec<(DnaHash, EntryHash)>> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let mut res = Vec::new();
+    /// Grab my private attachments
+    for (_ah, _ts, attachment) in query_private_attachments()? {
+        if attachment.manifest_eh == manifest_eh && !res.contains(&attachment.hrl) {
+            res.push(attachment.hrl);
+        }
+    }
+    /// Grab links
+    let links = get_links(manifest_eh, FilesLinkTypes::AttachedHrl, None)?;
+    for link in links {
+        let Ok(tag) = AttachmentTag::from_link_tag(&link.tag)
+            else { continue };
+        if !res.contains(&tag.hrl) {
+            res.push(tag.hrl);
+        }
+    }
+    /// Done
+    Ok(res)
 }
 
 
 ///
-fn root_path() -> ExternResult<TypedPath> {
-    let tp = Path::from(format!("{}", ATTACHMENTS_ROOT))
-        .typed(FilesLinkTypes::Attachment)?;
+fn hrl_path(hrl: (DnaHash, EntryHash)) -> ExternResult<TypedPath> {
+    let tp = hrl_to_path(&hrl)
+        .typed(FilesLinkTypes::AttachmentPath)?;
     Ok(tp)
 }
@@ -2,6 +2,7 @@ use hdk::prelude::*;
 //use zome_utils::*;
 use zome_delivery_api::*;
 use crate::encrypt::*;
+use crate::key_directory::publish_enc_pub_key;
 
 /// Zome Callback
 #[hdk_extern]
@@ -9,6 +10,7 @@ fn init(_: ()) -> ExternResult<InitCallbackResult> {
    /// Setup initial capabilities
    //init_caps()?;
    create_enc_key()?;
+   publish_enc_pub_key()?;
    /// Done
    debug!("*** zFiles.init() callback DONE");
    Ok(InitCallbackResult::Pass)
@@ -4,6 +4,7 @@ use zome_utils::*;
 use zome_delivery_types::*;
 use zome_delivery_api::*;
 use zome_files_integrity::*;
+use crate::find_file_by_data_hash::find_file;
 
 
 
@@ -11,32 +12,97 @@ use zome_files_integrity::*;
 pub struct WriteManifestInput {
     pub filename: String,
     pub filetype: String,
-    pub data_hash: String,
+    pub data_hash: ContentHash,
     pub orig_filesize: u64,
     pub chunks: Vec<EntryHash>,
+    /// Base64 content of a tiny File, to store it in its manifest instead of in chunks
+    #[serde(default)]
+    pub content: Option<String>,
+    /// Chunks have been written with write_encrypted_private_file_chunks()
+    #[serde(default)]
+    pub is_encrypted: bool,
 }
 
 
 /// Helper for commit_parcel_manifest()
+/// Return true as third value if one of my private Files with the same data hash is returned instead.
+/// Otherwise the last value is a File with the same data hash that was not substituted, e.g. one published by another agent.
+/// Call find_file_by_data_hash() before writing chunks to avoid writing them for nothing.
 #[hdk_extern]
-pub fn commit_private_file(input: WriteManifestInput) -> ExternResult<(EntryHash, ParcelDescription)> {
+pub fn commit_private_file(input: WriteManifestInput) -> ExternResult<(EntryHash, ParcelDescription, bool, Option<EntryHash>)> {
     std::panic::set_hook(Box::new(zome_panic_hook));
-    /// Form Description
+    /// Check if File already exists
+    let maybe_found = find_file(&input.data_hash.to_string(), true)?;
+    if let Some((eh, manifest, _is_mine)) = &maybe_found {
+        if manifest.description.visibility == EntryVisibility::Private {
+            return Ok((eh.to_owned(), manifest.description.to_owned(), true, None));
+        }
+    }
+    /// Commit Manifest
+    let manifest = form_file_manifest(input, EntryVisibility::Private)?;
+    let description = manifest.description.clone();
+    let eh = commit_file_manifest(manifest)?;
+    /// Done
+    return Ok((eh, description, false, maybe_found.map(|(eh, _, _)| eh)));
+}
+
+
+/// Form a File manifest, with its content inlined if it has been provided
+pub fn form_file_manifest(input: WriteManifestInput, visibility: EntryVisibility) -> ExternResult<ParcelManifest> {
+    if let Some(content) = &input.content {
+        if !input.chunks.is_empty() {
+            return error("An inline File cannot have chunks");
+        }
+        if input.orig_filesize > get_properties()?.max_inline_file_size {
+            return error("File is too big to be inlined");
+        }
+        if content.len() as u64 != (input.orig_filesize + 2) / 3 * 4 {
+            return error("Inline content does not match File size");
+        }
+        if input.is_encrypted {
+            return error("An inline File cannot be encrypted");
+        }
+    }
+    if input.is_encrypted && visibility != EntryVisibility::Private {
+        return error("Only private Files can be encrypted with my PrivEncKey");
+    }
     let description = ParcelDescription {
         name: input.filename,
         size: input.orig_filesize,
         zome_origin: FILES_DEFAULT_INTEGRITY_ZOME_NAME.into(),
-        visibility: EntryVisibility::Private,
-        kind_info: ParcelKind::Manifest(format!("{}::{}", FILE_TYPE_NAME, input.filetype)),
+        visibility,
+        kind_info: file_kind_info(&input.filetype, input.content.as_deref(), input.is_encrypted),
     };
-    /// Commit Manifest
-    let manifest = ParcelManifest {
-        data_hash: input.data_hash,
+    /// Descriptions can be copied in link tags, which are limited in size
+    if input.content.is_some() {
+        let size = encode(&description).map_err(|e| wasm_error!(e))?.len();
+        if size > MAX_INLINE_DESCRIPTION_SIZE {
+            return zome_error!("Inline File description is too big ({} > {} bytes). Use chunks instead.", size, MAX_INLINE_DESCRIPTION_SIZE);
+        }
+    }
+    Ok(ParcelManifest {
+        data_hash: input.data_hash.to_string(),
         chunks: input.chunks,
-        description: description.clone(),
-    };
-    let response = call_delivery_zome("commit_private_manifest", manifest)?;
+        description,
+    })
+}
+
+
+/// Commit a File manifest without deduplication, privately or publicly depending on its visibility.
+/// Public manifests are indexed by data hash.
+pub fn commit_file_manifest(manifest: ParcelManifest) -> ExternResult<EntryHash> {
+    if manifest.description.visibility == EntryVisibility::Private {
+        let response = call_delivery_zome("commit_private_manifest", manifest)?;
+        return decode_response(response);
+    }
+    let data_hash = manifest.data_hash.clone();
+    let is_encrypted = is_encrypted_file(&manifest.description);
+    let response = call_delivery_zome("publish_manifest", manifest)?;
     let eh: EntryHash = decode_response(response)?;
-    /// Done
-    return Ok((eh, description));
+    /// Index by data hash. Encrypted Files are not indexed since only their recipients can read them.
+    if is_encrypted {
+        return Ok(eh);
+    }
+    let _ = create_link(data_hash_path(&data_hash).path_entry_hash()?, eh.clone(), FilesLinkTypes::DataHash, LinkTag::from(()))?;
+    Ok(eh)
 }
@@ -0,0 +1,94 @@
+use hdk::prelude::*;
+use zome_utils::*;
+
+use zome_delivery_types::*;
+use zome_delivery_api::*;
+use crate::attach_to_hrl::query_private_attachments;
+use crate::private_folders::remove_file_from_folders;
+use crate::utils::*;
+use crate::tagging::*;
+
+
+/// Delete a File stored on my source-chain: its manifest, its chunks, its private tags, private attachments and folder placement.
+/// Fails if a distribution of this File is still in progress.
+#[hdk_extern]
+pub fn delete_private_file(manifest_eh: EntryHash) -> ExternResult<()> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    /// Make sure manifest is a private File
+    let manifest: ParcelManifest = get_typed_from_eh(manifest_eh.clone())?;
+    ensure_parcel_is_file(&manifest.description)?;
+    if manifest.description.visibility != EntryVisibility::Private {
+        return error("File is not private");
+    }
+    let manifest_ahs = query_create_ahs(&[manifest_eh.clone()])?;
+    if manifest_ahs.is_empty() {
+        return error("File not found on source-chain");
+    }
+    /// Make sure no distribution is in progress
+    ensure_no_distribution_in_progress(&manifest_eh)?;
+    /// Remove private tags
+    for tag in get_file_tags(manifest_eh.clone(), false)? {
+        untag_file(manifest_eh.clone(), tag, false)?;
+    }
+    /// Remove private attachments
+    for (ah, _ts, attachment) in query_private_attachments()? {
+        if attachment.manifest_eh == manifest_eh {
+            let _ = delete_entry(ah)?;
+        }
+    }
+    /// Remove from folders
+    remove_file_from_folders(&manifest_eh)?;
+    /// Delete chunks and manifest
+    delete_unused_private_chunks(&manifest_eh, manifest.chunks)?;
+    delete_parcel_entries(manifest_ahs)?;
+    /// Done
+    Ok(())
+}
+
+
+/// Delete the chunks of a private File that are not used by another live private File.
+/// Public copies of the chunks have the same EntryHash and must be kept.
+pub fn delete_unused_private_chunks(manifest_eh: &EntryHash, chunks: Vec<EntryHash>) -> ExternResult<()> {
+    let response = call_delivery_zome("get_all_private_manifests", ())?;
+    let manifests: Vec<(EntryHash, ParcelManifest)> = decode_response(response)?;
+    /// Deleted manifests are still returned by the delivery zome
+    let ehs: Vec<EntryHash> = manifests.iter()
+        .filter(|(eh, _)| eh != manifest_eh)
+        .map(|(eh, _)| eh.clone())
+        .collect();
+    let live_ehs: Vec<EntryHash> = query_live_creates(&ehs)?
+        .into_iter()
+        .map(|(_ah, eh)| eh)
+        .collect();
+    let chunks: Vec<EntryHash> = chunks.into_iter()
+        .filter(|chunk_eh| !manifests.iter().any(|(eh, other)| live_ehs.contains(eh) && other.chunks.contains(chunk_eh)))
+        .collect();
+    let chunk_ahs = query_live_creates_with_visibility(&chunks, Some(EntryVisibility::Private))?
+        .into_iter()
+        .map(|(ah, _eh)| ah)
+        .collect();
+    delete_parcel_entries(chunk_ahs)
+}
+
+
+/// Error if one of my distributions of this manifest is in progress,
+/// i.e. a recipient has not refused the File nor received it yet.
+pub fn ensure_no_distribution_in_progress(manifest_eh: &EntryHash) -> ExternResult<()> {
+    let response = call_delivery_zome("query_all_Distribution", ())?;
+    let distributions: Vec<(ActionHash, Timestamp, Distribution)> = decode_response(response)?;
+    for (distribution_ah, _ts, distribution) in distributions {
+        if &distribution.delivery_summary.parcel_reference.eh != manifest_eh {
+            continue;
+        }
+        for recipient in distribution.recipients {
+            let input = GetDeliveryStateInput { distribution_ah: distribution_ah.clone(), recipient };
+            let response = call_delivery_zome("get_delivery_state", input)?;
+            let state: DeliveryState = decode_response(response)?;
+            match state {
+                DeliveryState::ParcelRefused | DeliveryState::ParcelDelivered => {},
+                _ => return error("A distribution of this File is still in progress"),
+            }
+        }
+    }
+    Ok(())
+}
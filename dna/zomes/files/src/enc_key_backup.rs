@@ -0,0 +1,114 @@
+use hdk::prelude::*;
+use zome_utils::*;
+use base64::Engine;
+use crypto_box::aead::{Aead, generic_array::GenericArray};
+use zome_files_integrity::FilesEntry;
+use zome_files_integrity::priv_enc_key::PrivEncKey;
+use crate::encrypt::get_my_enc_keys;
+use crate::key_directory::ensure_enc_pub_key;
+
+
+///
+const MIN_PASSPHRASE_LENGTH: usize = 8;
+///
+const SALT_LENGTH: u32 = 16;
+///
+const NONCE_LENGTH: u32 = 24;
+
+
+/// My PrivEncKeys exported from my keystore to an X25519 key derived from a passphrase.
+/// `sender` is the X25519 key of the installation that made the backup.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct EncKeyBackup {
+   salt: Vec<u8>,
+   sender: X25519PubKey,
+   keys: Vec<(u32, XSalsa20Poly1305EncryptedData)>,
+}
+
+
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ImportEncKeyInput {
+   pub blob: String,
+   pub passphrase: String,
+}
+
+
+/// Export all my PrivEncKeys, wrapped with a key derived from the passphrase with Argon2id.
+/// Return a base64 blob to be given to import_enc_key() on another installation.
+#[hdk_extern]
+pub fn export_enc_key(passphrase: String) -> ExternResult<String> {
+   std::panic::set_hook(Box::new(zome_panic_hook));
+   if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+      return zome_error!("Passphrase must be at least {} characters", MIN_PASSPHRASE_LENGTH);
+   }
+   let salt = random_bytes(SALT_LENGTH)?.to_vec();
+   let secret = derive_secret(&passphrase, &salt)?;
+   let recipient = X25519PubKey::from(*secret.public_key().as_bytes());
+   /// Export each key from my keystore
+   let sender = ensure_enc_pub_key(())?;
+   let mut keys = Vec::new();
+   for key in get_my_enc_keys()? {
+      let sealed = x_salsa20_poly1305_shared_secret_export(sender.clone(), recipient.clone(), key.value)?;
+      keys.push((key.id, sealed));
+   }
+   /// Encode
+   let backup = EncKeyBackup { salt, sender, keys };
+   let io = ExternIO::encode(backup)
+      .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?;
+   Ok(base64::engine::general_purpose::STANDARD.encode(io.0))
+}
+
+
+/// Install the PrivEncKeys of a backup made by export_enc_key() as new entries on my source-chain.
+/// Imported keys keep their id so data encrypted with them stays decryptable.
+/// Return the ids of the imported keys.
+#[hdk_extern]
+pub fn import_enc_key(input: ImportEncKeyInput) -> ExternResult<Vec<u32>> {
+   std::panic::set_hook(Box::new(zome_panic_hook));
+   let bytes = base64::engine::general_purpose::STANDARD.decode(input.blob)
+      .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Key backup is not base64: {}", e))))?;
+   let backup: EncKeyBackup = ExternIO(bytes).decode()
+      .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?;
+   let secret = derive_secret(&input.passphrase, &backup.salt)?;
+   /// Open with the passphrase key, then seal again for my keystore
+   let my_key = ensure_enc_pub_key(())?;
+   let opening_box = crypto_box::SalsaBox::new(&to_crypto_box_key(&backup.sender)?, &secret);
+   let sealing_box = crypto_box::SalsaBox::new(&to_crypto_box_key(&my_key)?, &secret);
+   let wrapping_key = X25519PubKey::from(*secret.public_key().as_bytes());
+   let mut ids = Vec::new();
+   for (id, sealed) in backup.keys {
+      let raw_key = opening_box
+         .decrypt(GenericArray::from_slice(sealed.as_nonce_ref().as_ref()), sealed.as_encrypted_data_ref())
+         .map_err(|_| wasm_error!(WasmErrorInner::Guest("Wrong passphrase or corrupt key backup".to_string())))?;
+      let nonce: [u8; NONCE_LENGTH as usize] = random_bytes(NONCE_LENGTH)?.to_vec().try_into()
+         .map_err(|_| wasm_error!(WasmErrorInner::Guest("Failed to generate nonce".to_string())))?;
+      let resealed = sealing_box
+         .encrypt(GenericArray::from_slice(&nonce), raw_key.as_slice())
+         .map_err(|_| wasm_error!(WasmErrorInner::Guest("Failed to seal key for keystore".to_string())))?;
+      let encrypted = XSalsa20Poly1305EncryptedData::new(XSalsa20Poly1305Nonce::from(nonce), resealed);
+      let key_ref = x_salsa20_poly1305_shared_secret_ingest(my_key.clone(), wrapping_key.clone(), encrypted, None)?;
+      let _ah = create_entry(FilesEntry::PrivEncKey(PrivEncKey::new_imported(key_ref, id)))?;
+      ids.push(id);
+   }
+   /// Done
+   Ok(ids)
+}
+
+
+/// Derive an X25519 secret key from a passphrase with Argon2id (default parameters)
+fn derive_secret(passphrase: &str, salt: &[u8]) -> ExternResult<crypto_box::SecretKey> {
+   let mut bytes = [0u8; crypto_box::KEY_SIZE];
+   argon2::Argon2::default()
+      .hash_password_into(passphrase.as_bytes(), salt, &mut bytes)
+      .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to derive key from passphrase: {}", e))))?;
+   Ok(crypto_box::SecretKey::from(bytes))
+}
+
+
+///
+fn to_crypto_box_key(key: &X25519PubKey) -> ExternResult<crypto_box::PublicKey> {
+   let bytes: [u8; crypto_box::KEY_SIZE] = key.as_ref().try_into()
+      .map_err(|_| wasm_error!(WasmErrorInner::Guest("X25519 public key has wrong length".to_string())))?;
+   Ok(crypto_box::PublicKey::from(bytes))
+}
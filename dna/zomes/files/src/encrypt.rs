@@ -1,56 +1,267 @@
 use hdk::prelude::*;
 use zome_utils::*;
-use zome_files_integrity::{FilesEntry, FilesEntryTypes};
+use base64::Engine;
+use zome_delivery_types::{ParcelChunk, ParcelDescription, ParcelManifest};
+use zome_files_integrity::{FilesEntry, FilesEntryTypes, FileKey, file_kind_info, get_properties, is_encrypted_file};
 use zome_files_integrity::priv_enc_key::PrivEncKey;
 
 
+/// Prefix of the data of an encrypted chunk.
+/// Base64 data never contains ':' so plaintext chunks cannot be mistaken for encrypted ones.
+pub const ENCRYPTED_CHUNK_PREFIX: &str = "encrypted::";
+
+/// Upper bound of the bytes the msgpack-encoded XSalsa20Poly1305EncryptedData adds to the plaintext:
+/// 24 bytes of nonce, 16 bytes of MAC and the encoding of the struct's field names and lengths.
+const ENCRYPTION_OVERHEAD: u64 = 128;
+
+/// Longest key id tag: "<u32::MAX>::"
+const MAX_KEY_ID_TAG_LEN: u64 = 12;
+
+
+/// Data encrypted with one of my PrivEncKeys, tagged with the key's id
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KeyedEncryptedData {
+   pub key_id: u32,
+   pub data: XSalsa20Poly1305EncryptedData,
+}
+
+
+/// Data encrypted before key rotation existed is not tagged with a key id
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum EncryptedDataInput {
+   Keyed(KeyedEncryptedData),
+   Legacy(XSalsa20Poly1305EncryptedData),
+}
+
+
 ///
 pub fn create_enc_key() -> ExternResult<()> {
    let res = x_salsa20_poly1305_shared_secret_create_random(None)?;
-   let _ah = create_entry(FilesEntry::PrivEncKey(PrivEncKey::new(res)))?;
+   let _ah = create_entry(FilesEntry::PrivEncKey(PrivEncKey::new(res, 0)))?;
    Ok(())
 }
 
 
-///
-pub fn get_my_enc_key() -> ExternResult<XSalsa20Poly1305KeyRef> {
-   let tuples = get_all_typed_local::<PrivEncKey>(FilesEntryTypes::PrivEncKey.try_into().unwrap())?;
-   if tuples.is_empty() {
-      return error("No Private encryption key found");
+/// Create a new PrivEncKey that will be used for all new encryptions. Return its id.
+/// Data encrypted with older keys stays decryptable.
+/// If asked, stored private Files are re-encrypted to the new key in the background.
+#[hdk_extern]
+pub fn rotate_enc_key(reencrypt: bool) -> ExternResult<u32> {
+   std::panic::set_hook(Box::new(zome_panic_hook));
+   let id = get_my_enc_key()?.id + 1;
+   let res = x_salsa20_poly1305_shared_secret_create_random(None)?;
+   let _ah = create_entry(FilesEntry::PrivEncKey(PrivEncKey::new(res, id)))?;
+   if reencrypt {
+      schedule("reencrypt_private_files")?;
    }
-   if tuples.len() > 1 {
-      return error("Many Private encryption keys found");
+   Ok(id)
+}
+
+
+/// Return my newest PrivEncKey
+pub fn get_my_enc_key() -> ExternResult<PrivEncKey> {
+   let Some(key) = get_my_enc_keys()?.pop()
+      else { return error("No Private encryption key found") };
+   Ok(key)
+}
+
+
+/// Return my PrivEncKeys with the given id, newest first.
+/// Many keys can have the same id when keys have been imported from another installation.
+pub fn get_enc_keys_by_id(id: u32) -> ExternResult<Vec<XSalsa20Poly1305KeyRef>> {
+   let keys: Vec<XSalsa20Poly1305KeyRef> = get_my_enc_keys()?
+      .into_iter()
+      .rev()
+      .filter(|key| key.id == id)
+      .map(|key| key.value)
+      .collect();
+   if keys.is_empty() {
+      return zome_error!("No Private encryption key found with id {}", id);
    }
-   let res: Vec<PrivEncKey> = tuples.into_iter()
+   Ok(keys)
+}
+
+
+/// Return all my PrivEncKeys, sorted by id then by creation
+pub fn get_my_enc_keys() -> ExternResult<Vec<PrivEncKey>> {
+   let tuples = get_all_typed_local::<PrivEncKey>(FilesEntryTypes::PrivEncKey.try_into().unwrap())?;
+   let mut keys: Vec<PrivEncKey> = tuples.into_iter()
        .map(|(_, _, key)| key)
        .collect();
-   Ok(res.first().unwrap().value.clone())
+   keys.sort_by_key(|key| key.id);
+   Ok(keys)
 }
 
 
 /// Input: XSalsa20Poly1305Data
 /// Output: XSalsa20Poly1305EncryptedData
+/// Encrypted with my newest PrivEncKey, without its id: decrypt_data() then tries all my keys.
 #[hdk_extern]
 fn encrypt_data(data: XSalsa20Poly1305Data) -> ExternResult<XSalsa20Poly1305EncryptedData> {
-   //let salsa: XSalsa20Poly1305Data = data.into();
    let my_priv_key = get_my_enc_key()?;
    /// Encrypt
-   let encrypted = x_salsa20_poly1305_encrypt(my_priv_key, data)?;
+   let encrypted = x_salsa20_poly1305_encrypt(my_priv_key.value, data)?;
    /// Done
    Ok(encrypted)
 }
 
 
-/// Input: XSalsa20Poly1305EncryptedData
-/// Output: XSalsa20Poly1305Data
+/// Input: XSalsa20Poly1305Data
+/// Output: KeyedEncryptedData
+/// Same as encrypt_data() but tagged with the id of the key used, so decrypt_data() only tries keys with that id.
 #[hdk_extern]
-fn decrypt_data(data: XSalsa20Poly1305EncryptedData) -> ExternResult<XSalsa20Poly1305Data> {
-   debug!("decrypt_data() {:?}", data);
-   //let salsa: XSalsa20Poly1305EncryptedData = data.into();
+fn encrypt_data_with_key_id(data: XSalsa20Poly1305Data) -> ExternResult<KeyedEncryptedData> {
    let my_priv_key = get_my_enc_key()?;
    /// Encrypt
-   let Some(data) = x_salsa20_poly1305_decrypt(my_priv_key, data)?
-    else { return error("Failed to decrypt data with key")};
+   let encrypted = x_salsa20_poly1305_encrypt(my_priv_key.value, data)?;
    /// Done
-   Ok(data)
+   Ok(KeyedEncryptedData { key_id: my_priv_key.id, data: encrypted })
+}
+
+
+/// Input: KeyedEncryptedData, or XSalsa20Poly1305EncryptedData from encrypt_data()
+/// Output: XSalsa20Poly1305Data
+#[hdk_extern]
+fn decrypt_data(input: EncryptedDataInput) -> ExternResult<XSalsa20Poly1305Data> {
+   debug!("decrypt_data() {:?}", input);
+   let (keys, data) = match input {
+      EncryptedDataInput::Keyed(keyed) => (get_enc_keys_by_id(keyed.key_id)?, keyed.data),
+      /// Untagged data can have been encrypted with any of my keys, try the newest first
+      EncryptedDataInput::Legacy(data) => {
+         let keys: Vec<XSalsa20Poly1305KeyRef> = get_my_enc_keys()?.into_iter().rev().map(|key| key.value).collect();
+         (keys, data)
+      },
+   };
+   /// Decrypt
+   for my_priv_key in keys {
+      if let Some(data) = x_salsa20_poly1305_decrypt(my_priv_key, data.clone())? {
+         return Ok(data);
+      }
+   }
+   error("Failed to decrypt data with key")
+}
+
+
+/// Return the FileKey of an encrypted public File.
+/// Return None if the File is not encrypted or is private: private chunks are decrypted with my PrivEncKeys.
+pub fn get_file_key(manifest: &ParcelManifest) -> ExternResult<Option<XSalsa20Poly1305KeyRef>> {
+   if !is_encrypted_file(&manifest.description) || manifest.description.visibility == EntryVisibility::Private {
+      return Ok(None);
+   }
+   Ok(Some(get_public_file_key(manifest)?.value))
+}
+
+
+/// Return the data hash of a File's content.
+/// An encrypted public File's manifest has the data hash of its ciphertext, its FileKey has the one of its content.
+pub fn get_file_data_hash(manifest: &ParcelManifest) -> ExternResult<String> {
+   if !is_encrypted_file(&manifest.description) || manifest.description.visibility == EntryVisibility::Private {
+      return Ok(manifest.data_hash.clone());
+   }
+   Ok(get_public_file_key(manifest)?.data_hash)
+}
+
+
+/// Return the description of a File's content.
+/// An encrypted public File's manifest has a neutral description, its FileKey has its name, size and type.
+pub fn get_file_description(manifest: &ParcelManifest) -> ExternResult<ParcelDescription> {
+   if !is_encrypted_file(&manifest.description) || manifest.description.visibility == EntryVisibility::Private {
+      return Ok(manifest.description.clone());
+   }
+   let file_key = get_public_file_key(manifest)?;
+   Ok(ParcelDescription {
+      name: file_key.name,
+      size: file_key.size,
+      kind_info: file_kind_info(&file_key.filetype, None, true),
+      ..manifest.description.clone()
+   })
+}
+
+
+/// Return the FileKey I sent or received for an encrypted public File
+fn get_public_file_key(manifest: &ParcelManifest) -> ExternResult<FileKey> {
+   let manifest_eh = hash_entry(manifest.clone())?;
+   let tuples = get_all_typed_local::<FileKey>(FilesEntryTypes::FileKey.try_into().unwrap())?;
+   let Some((_, _, file_key)) = tuples.into_iter().rev().find(|(_, _, file_key)| file_key.manifest_eh == manifest_eh)
+      else { return error("No key found for this encrypted File") };
+   Ok(file_key)
+}
+
+
+/// Encrypt the data of a chunk with the given key: "<ENCRYPTED_CHUNK_PREFIX>[<key_id>::]<base64>".
+/// Chunks encrypted with one of my PrivEncKeys are tagged with its id.
+pub fn encrypt_chunk(chunk: ParcelChunk, key: &XSalsa20Poly1305KeyRef, maybe_key_id: Option<u32>) -> ExternResult<ParcelChunk> {
+   let encrypted = x_salsa20_poly1305_encrypt(key.clone(), chunk.data.into_bytes().into())?;
+   let io = ExternIO::encode(encrypted)
+      .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?;
+   let encoded = base64::engine::general_purpose::STANDARD.encode(io.0);
+   let data = match maybe_key_id {
+      Some(key_id) => format!("{}{}::{}", ENCRYPTED_CHUNK_PREFIX, key_id, encoded),
+      None => format!("{}{}", ENCRYPTED_CHUNK_PREFIX, encoded),
+   };
+   Ok(ParcelChunk { data, ..chunk })
+}
+
+
+/// Size of the plaintext chunks a File must be split into so that its encrypted chunks fit in maxChunkSize.
+/// Encrypted data is base64 encoded, so it is a third larger than the plaintext.
+#[hdk_extern]
+pub fn get_encrypted_chunk_size(_: ()) -> ExternResult<u32> {
+   std::panic::set_hook(Box::new(zome_panic_hook));
+   let max_chunk_size = get_properties()?.max_chunk_size as u64;
+   let chunk_size = encrypted_chunk_size(max_chunk_size);
+   if chunk_size == 0 {
+      return error("maxChunkSize is too small for encrypted chunks");
+   }
+   Ok(chunk_size as u32)
+}
+
+
+/// Largest multiple of 4 that, once encrypted and tagged, fits in max_chunk_size
+pub fn encrypted_chunk_size(max_chunk_size: u64) -> u64 {
+   let b64_len = max_chunk_size.saturating_sub(ENCRYPTED_CHUNK_PREFIX.len() as u64 + MAX_KEY_ID_TAG_LEN);
+   let encrypted_len = b64_len / 4 * 3;
+   encrypted_len.saturating_sub(ENCRYPTION_OVERHEAD) / 4 * 4
+}
+
+
+/// Return the id of the PrivEncKey a chunk is tagged with, if any
+pub fn get_chunk_key_id(chunk: &ParcelChunk) -> Option<u32> {
+   split_encrypted_data(&chunk.data)?.0
+}
+
+
+/// Decrypt the data of a chunk if it is encrypted, with the File's key or else with my PrivEncKeys
+pub fn decrypt_chunk(chunk: ParcelChunk, maybe_key: Option<&XSalsa20Poly1305KeyRef>) -> ExternResult<ParcelChunk> {
+   let Some((maybe_key_id, encoded)) = split_encrypted_data(&chunk.data)
+      else { return Ok(chunk) };
+   let keys = match (maybe_key_id, maybe_key) {
+      (Some(key_id), _) => get_enc_keys_by_id(key_id)?,
+      (None, Some(key)) => vec![key.to_owned()],
+      /// Untagged private chunks were encrypted with my first key, of which I can have many if keys were imported
+      (None, None) => get_enc_keys_by_id(0)?,
+   };
+   let bytes = base64::engine::general_purpose::STANDARD.decode(encoded)
+      .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Encrypted chunk is not base64: {}", e))))?;
+   let encrypted: XSalsa20Poly1305EncryptedData = ExternIO(bytes).decode()
+      .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?;
+   for key in keys {
+      let Some(decrypted) = x_salsa20_poly1305_decrypt(key, encrypted.clone())?
+         else { continue };
+      let data = String::from_utf8(decrypted.as_ref().to_vec())
+         .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Decrypted chunk is not a string: {}", e))))?;
+      return Ok(ParcelChunk { data, ..chunk });
+   }
+   error("Failed to decrypt chunk with key")
+}
+
+
+/// Return the key id, if tagged, and the base64 ciphertext of encrypted chunk data
+fn split_encrypted_data(data: &str) -> Option<(Option<u32>, &str)> {
+   let rest = data.strip_prefix(ENCRYPTED_CHUNK_PREFIX)?;
+   match rest.split_once("::") {
+      Some((key_id, encoded)) => Some((Some(key_id.parse().ok()?), encoded)),
+      None => Some((None, rest)),
+   }
 }
@@ -0,0 +1,250 @@
+use hdk::prelude::*;
+use zome_utils::*;
+
+use zome_delivery_types::*;
+use zome_delivery_api::*;
+use zome_files_integrity::*;
+use crate::commit_private_file::commit_file_manifest;
+use crate::encrypt::{encrypt_chunk, get_encrypted_chunk_size, get_file_data_hash, get_file_description};
+use crate::key_directory::*;
+use crate::respond_to_file_notice::get_notice_of_parcel;
+use crate::utils::*;
+use crate::write_file_chunk::write_public_file_chunks;
+
+
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SendEncryptedFileInput {
+    pub manifest_eh: EntryHash,
+    pub strategy: DistributionStrategy,
+    pub recipients: Vec<AgentPubKey>,
+}
+
+
+/// Send a File to many recipients by publishing it once, encrypted with a random key,
+/// and sending each recipient only the key, sealed with its X25519 key.
+/// The public manifest has a neutral description and, like the chunks, the data hash of the ciphertext,
+/// so they do not reveal which File it is. The File's name, size, type and data hash are sent with the sealed key.
+/// Return the encrypted public File's EntryHash and the Distributions' ActionHashes.
+#[hdk_extern]
+pub fn send_encrypted_file(input: SendEncryptedFileInput) -> ExternResult<(EntryHash, Vec<ActionHash>)> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let manifest: ParcelManifest = get_typed_from_eh(input.manifest_eh)?;
+    ensure_parcel_is_file(&manifest.description)?;
+    if input.recipients.is_empty() {
+        return error("No recipients");
+    }
+    let file_data_hash = get_file_data_hash(&manifest)?;
+    let file_description = get_file_description(&manifest)?;
+    /// Get recipients' keys first, so nothing is published if one is missing
+    let my_x25519 = ensure_enc_pub_key(())?;
+    let mut recipients = Vec::new();
+    for recipient in input.recipients {
+        let Some(recipient_key) = get_enc_pub_keys(recipient.clone())?.into_iter().next()
+            else { return zome_error!("Agent {} has not published an encryption key", recipient) };
+        recipients.push((recipient, recipient_key));
+    }
+    /// Encrypt chunks, split so they fit in maxChunkSize once encrypted.
+    /// Inline content becomes a chunk so it is not readable in the description.
+    let key = x_salsa20_poly1305_shared_secret_create_random(None)?;
+    let chunk_size = get_encrypted_chunk_size(())? as usize;
+    let encrypted = resplit_chunks(get_file_chunks(&manifest)?, chunk_size)
+        .into_iter()
+        .map(|chunk| encrypt_chunk(chunk, &key, None))
+        .collect::<ExternResult<Vec<ParcelChunk>>>()?;
+    let data_hash = compute_data_hash(&encrypted, HashAlgorithm::Sha256).to_string();
+    let encrypted = encrypted.into_iter()
+        .map(|chunk| ParcelChunk { data_hash: data_hash.clone(), ..chunk })
+        .collect();
+    /// Publish chunks and manifest, and keep its key.
+    /// The public size is the capacity of the chunks, which their count reveals anyway.
+    let chunk_ehs = write_public_file_chunks(encrypted)?;
+    let filetype = get_filetype(&file_description).unwrap_or_default();
+    let description = ParcelDescription {
+        name: ENCRYPTED_FILE_NAME.to_string(),
+        size: chunk_ehs.len() as u64 * (chunk_size as u64 / 4 * 3),
+        zome_origin: FILES_DEFAULT_INTEGRITY_ZOME_NAME.into(),
+        visibility: EntryVisibility::Public,
+        kind_info: file_kind_info(ENCRYPTED_FILE_TYPE, None, true),
+    };
+    let public_manifest = ParcelManifest {
+        description,
+        chunks: chunk_ehs,
+        data_hash: data_hash.clone(),
+    };
+    let eh = commit_file_manifest(public_manifest)?;
+    let file_key = FileKey {
+        manifest_eh: eh.clone(),
+        value: key.clone(),
+        data_hash: file_data_hash.clone(),
+        name: file_description.name.clone(),
+        size: file_description.size,
+        filetype: filetype.clone(),
+    };
+    let _ah = create_entry(FilesEntry::FileKey(file_key))?;
+    /// Send sealed key to each recipient, as an empty manifest
+    let mut distribution_ahs = Vec::new();
+    for (recipient, recipient_key) in recipients {
+        let sealed_key = x_salsa20_poly1305_shared_secret_export(my_x25519.clone(), recipient_key, key.clone())?;
+        let sealed = SealedFileKey {
+            manifest_eh: eh.clone(),
+            sealed_key,
+            data_hash: file_data_hash.clone(),
+            name: file_description.name.clone(),
+            size: file_description.size,
+            filetype: filetype.clone(),
+        };
+        let sealed_description = ParcelDescription {
+            name: file_description.name.clone(),
+            size: 0,
+            zome_origin: FILES_DEFAULT_INTEGRITY_ZOME_NAME.into(),
+            visibility: EntryVisibility::Private,
+            kind_info: sealed.to_kind_info()?,
+        };
+        let sealed_manifest = ParcelManifest {
+            description: sealed_description.clone(),
+            chunks: Vec::new(),
+            data_hash: data_hash.clone(),
+        };
+        let response = call_delivery_zome("commit_private_manifest", sealed_manifest)?;
+        let sealed_eh: EntryHash = decode_response(response)?;
+        let distribute_input = DistributeParcelInput {
+            recipients: vec![recipient],
+            strategy: input.strategy.clone(),
+            parcel_reference: ParcelReference { eh: sealed_eh, description: sealed_description },
+        };
+        let response = call_delivery_zome("distribute_parcel", distribute_input)?;
+        distribution_ahs.push(decode_response(response)?);
+    }
+    /// Done
+    Ok((eh, distribution_ahs))
+}
+
+
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SealedFileNotice {
+    pub sender: AgentPubKey,
+    pub notice_eh: EntryHash,
+    pub parcel_eh: EntryHash,
+    /// Description of the File whose key is sealed, encrypted public Files only have a neutral one
+    pub description: ParcelDescription,
+}
+
+
+/// Return the notices of sealed keys waiting for a response, to accept with accept_encrypted_file().
+/// get_unreplied_notices() only returns notices of Files.
+#[hdk_extern]
+pub fn get_unreplied_sealed_key_notices(_: ()) -> ExternResult<Vec<SealedFileNotice>> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let response = call_delivery_zome("query_all_DeliveryNotice", ())?;
+    let notices: Vec<(EntryHash, Timestamp, DeliveryNotice)> = decode_response(response)?;
+    let mut res = Vec::new();
+    for (notice_eh, _ts, notice) in notices {
+        let Some(sealed) = SealedFileKey::from_kind_info(&notice.summary.parcel_reference.description.kind_info)
+            else { continue };
+        let response = call_delivery_zome("get_notice_state", notice_eh.clone())?;
+        let (state, _pct): (NoticeState, usize) = decode_response(response)?;
+        if state != NoticeState::Unreplied {
+            continue;
+        }
+        let description = ParcelDescription {
+            name: sealed.name,
+            size: sealed.size,
+            zome_origin: FILES_DEFAULT_INTEGRITY_ZOME_NAME.into(),
+            visibility: EntryVisibility::Public,
+            kind_info: file_kind_info(&sealed.filetype, None, true),
+        };
+        res.push(SealedFileNotice {
+            sender: notice.sender,
+            notice_eh,
+            parcel_eh: notice.summary.parcel_reference.eh,
+            description,
+        });
+    }
+    Ok(res)
+}
+
+
+/// Accept the notice of a sealed key, like accept_file_share(), and keep the key so the encrypted File can be read.
+/// Sealed keys are refused with refuse_file_share().
+/// Return the EntryHash of the encrypted public File.
+#[hdk_extern]
+pub fn accept_encrypted_file(parcel_eh: EntryHash) -> ExternResult<EntryHash> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let (notice_eh, notice) = get_notice_of_parcel(parcel_eh)?;
+    let Some(sealed) = SealedFileKey::from_kind_info(&notice.summary.parcel_reference.description.kind_info)
+        else { return error("Parcel is not a sealed File key") };
+    if !keep_sealed_key(&sealed, &notice.sender, &get_my_enc_pub_keys()?)? {
+        return zome_error!("Failed to ingest key sealed by {}", notice.sender);
+    }
+    let response = call_delivery_zome("respond_to_notice", RespondToNoticeInput { notice_eh, has_accepted: true })?;
+    let _eh: EntryHash = decode_response(response)?;
+    Ok(sealed.manifest_eh)
+}
+
+
+/// Keep the keys of the sealed key notices I have already accepted, e.g. with accept_file_share().
+/// Unreplied notices are left for me to accept or refuse.
+/// Return the EntryHashes of the encrypted public Files I can read.
+#[hdk_extern]
+pub fn receive_encrypted_files(_: ()) -> ExternResult<Vec<EntryHash>> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let my_keys = get_my_enc_pub_keys()?;
+    let response = call_delivery_zome("query_all_DeliveryNotice", ())?;
+    let notices: Vec<(EntryHash, Timestamp, DeliveryNotice)> = decode_response(response)?;
+    let mut res = Vec::new();
+    for (notice_eh, _ts, notice) in notices {
+        let Some(sealed) = SealedFileKey::from_kind_info(&notice.summary.parcel_reference.description.kind_info)
+            else { continue };
+        let response = call_delivery_zome("get_notice_state", notice_eh)?;
+        let (state, _pct): (NoticeState, usize) = decode_response(response)?;
+        if !matches!(state, NoticeState::Accepted | NoticeState::PartiallyReceived | NoticeState::Received) {
+            continue;
+        }
+        if !keep_sealed_key(&sealed, &notice.sender, &my_keys)? {
+            debug!("Failed to ingest key sealed by {}", notice.sender);
+            continue;
+        }
+        res.push(sealed.manifest_eh);
+    }
+    Ok(res)
+}
+
+
+/// Store the key of a sealed key notice as a FileKey, unless already stored.
+/// Return false if the key could not be ingested.
+fn keep_sealed_key(sealed: &SealedFileKey, sender: &AgentPubKey, my_keys: &[X25519PubKey]) -> ExternResult<bool> {
+    let is_known = get_all_typed_local::<FileKey>(FilesEntryTypes::FileKey.try_into().unwrap())?
+        .into_iter()
+        .any(|(_, _, file_key)| file_key.manifest_eh == sealed.manifest_eh);
+    if is_known {
+        return Ok(true);
+    }
+    let Some(key) = ingest_sealed_key(sealed, sender, my_keys)?
+        else { return Ok(false) };
+    let file_key = FileKey {
+        manifest_eh: sealed.manifest_eh.clone(),
+        value: key,
+        data_hash: sealed.data_hash.clone(),
+        name: sealed.name.clone(),
+        size: sealed.size,
+        filetype: sealed.filetype.clone(),
+    };
+    let _ah = create_entry(FilesEntry::FileKey(file_key))?;
+    Ok(true)
+}
+
+
+/// Try all my keys and the sender's keys
+fn ingest_sealed_key(sealed: &SealedFileKey, sender: &AgentPubKey, my_keys: &[X25519PubKey]) -> ExternResult<Option<XSalsa20Poly1305KeyRef>> {
+    let sender_keys = get_enc_pub_keys(sender.to_owned())?;
+    for my_key in my_keys {
+        for sender_key in &sender_keys {
+            if let Ok(key) = x_salsa20_poly1305_shared_secret_ingest(my_key.clone(), sender_key.clone(), sealed.sealed_key.clone(), None) {
+                return Ok(Some(key));
+            }
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,52 @@
+use hdk::prelude::*;
+use zome_utils::*;
+
+use zome_delivery_types::*;
+use zome_files_integrity::*;
+use crate::get_files::{get_local_public_files, get_private_files};
+use crate::unpublish_file::is_unpublished;
+
+
+/// Look for a File with the given data hash,
+/// in my private Files, then in my public Files, then in Files published by other agents.
+/// Return true as last value if the File is one of mine.
+/// Meant to be called before writing any chunk, to skip uploading a File I already have.
+#[hdk_extern]
+pub fn find_file_by_data_hash(data_hash: ContentHash) -> ExternResult<Option<(EntryHash, ParcelManifest, bool)>> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    find_file(&data_hash.to_string(), true)
+}
+
+
+/// Same as find_file_by_data_hash().
+/// A data hash that cannot be parsed is compared as is.
+pub fn find_file(data_hash: &str, include_private: bool) -> ExternResult<Option<(EntryHash, ParcelManifest, bool)>> {
+    /// Search my Files
+    let mut my_files = get_local_public_files(())?;
+    if include_private {
+        my_files = [get_private_files(())?, my_files].concat();
+    }
+    if let Some((eh, manifest)) = my_files.into_iter().find(|(_, manifest)| same_data_hash(data_hash, &manifest.data_hash) && !is_encrypted_file(&manifest.description)) {
+        return Ok(Some((eh, manifest, true)));
+    }
+    /// Search Files published by other agents
+    let index_hash = ContentHash::parse(data_hash)
+        .map(|hash| hash.to_string())
+        .unwrap_or(data_hash.to_string());
+    let links = get_links(data_hash_path(&index_hash).path_entry_hash()?, FilesLinkTypes::DataHash, None)?;
+    for link in links {
+        let Some(eh) = link.target.into_entry_hash()
+            else { continue };
+        if is_unpublished(eh.clone())? {
+            continue;
+        }
+        let Ok(manifest) = get_typed_from_eh::<ParcelManifest>(eh.clone())
+            else { continue };
+        if is_encrypted_file(&manifest.description) {
+            continue;
+        }
+        return Ok(Some((eh, manifest, false)));
+    }
+    /// Done
+    Ok(None)
+}
@@ -3,7 +3,7 @@ use zome_utils::*;
 
 use zome_delivery_types::*;
 use zome_delivery_api::*;
-use crate::utils::ensure_parcel_is_file;
+use crate::utils::{ensure_parcel_is_file, query_live_creates};
 
 
 /// Return ehs of all ParcelManifest for type FILE_TYPE_NAME
@@ -13,8 +13,17 @@ pub fn get_private_files(_:()) -> ExternResult<Vec<(EntryHash, ParcelManifest)>>
     let response = call_delivery_zome("get_all_private_manifests", ())?;
     let manifests: Vec<(EntryHash, ParcelManifest)> = decode_response(response)?;
     debug!("get_private_files() manifests found: {}", manifests.len());
+    /// Filter out deleted Files
+    let ehs: Vec<EntryHash> = manifests.iter().map(|(eh, _)| eh.to_owned()).collect();
+    let live_ehs: Vec<EntryHash> = query_live_creates(&ehs)?
+        .into_iter()
+        .map(|(_ah, eh)| eh)
+        .collect();
     let mut file_manifests = Vec::new();
     for (eh, manifest) in manifests {
+        if !live_ehs.contains(&eh) {
+            continue;
+        }
         ///Make sure manifest exists and is of File type.
         if let Ok(()) = ensure_parcel_is_file(&manifest.description) {
             file_manifests.push((eh, manifest));
@@ -31,8 +40,17 @@ pub fn get_local_public_files(_:()) -> ExternResult<Vec<(EntryHash, ParcelManife
     let response = call_delivery_zome("get_all_local_public_manifests", ())?;
     let manifests: Vec<(EntryHash, ParcelManifest)> = decode_response(response)?;
     debug!("get_local_public_files() manifests found: {}", manifests.len());
+    /// Filter out unpublished Files
+    let ehs: Vec<EntryHash> = manifests.iter().map(|(eh, _)| eh.to_owned()).collect();
+    let live_ehs: Vec<EntryHash> = query_live_creates(&ehs)?
+        .into_iter()
+        .map(|(_ah, eh)| eh)
+        .collect();
     let mut file_manifests = Vec::new();
     for (eh, manifest) in manifests {
+        if !live_ehs.contains(&eh) {
+            continue;
+        }
         ///Make sure manifest exists and is of File type.
         if let Ok(()) = ensure_parcel_is_file(&manifest.description) {
             file_manifests.push((eh, manifest));
@@ -0,0 +1,107 @@
+use hdk::prelude::*;
+use zome_utils::*;
+
+use zome_files_integrity::*;
+
+
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EncryptForInput {
+   pub recipients: Vec<AgentPubKey>,
+   pub data: XSalsa20Poly1305Data,
+}
+
+
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DecryptFromInput {
+   pub sender: AgentPubKey,
+   pub data: XSalsa20Poly1305EncryptedData,
+}
+
+
+/// Create an X25519 keypair in my keystore and publish its public key in the key directory
+pub fn publish_enc_pub_key() -> ExternResult<X25519PubKey> {
+   let value = create_x25519_keypair()?;
+   let key = EncPubKey::new(value.clone());
+   let eh = hash_entry(key.clone())?;
+   let _ah = create_entry(FilesEntry::EncPubKey(key))?;
+   let _ = create_link(agent_info()?.agent_initial_pubkey, eh, FilesLinkTypes::AgentEncPubKey, LinkTag::from(()))?;
+   Ok(value)
+}
+
+
+/// Return my latest published X25519 public key, publishing one if there is none.
+/// Agents installed before the key directory existed must call this once to be reachable.
+#[hdk_extern]
+pub fn ensure_enc_pub_key(_: ()) -> ExternResult<X25519PubKey> {
+   std::panic::set_hook(Box::new(zome_panic_hook));
+   if let Some(key) = get_my_enc_pub_keys()?.into_iter().next() {
+      return Ok(key);
+   }
+   publish_enc_pub_key()
+}
+
+
+/// Return the X25519 public keys published by an agent, newest first
+#[hdk_extern]
+pub fn get_enc_pub_keys(agent: AgentPubKey) -> ExternResult<Vec<X25519PubKey>> {
+   std::panic::set_hook(Box::new(zome_panic_hook));
+   let mut links = get_links(agent, FilesLinkTypes::AgentEncPubKey, None)?;
+   links.sort_by_key(|link| std::cmp::Reverse(link.timestamp));
+   let mut keys = Vec::new();
+   for link in links {
+      let Some(eh) = link.target.into_entry_hash()
+         else { continue };
+      let Ok(key) = get_typed_from_eh::<EncPubKey>(eh)
+         else { continue };
+      if !keys.contains(&key.value) {
+         keys.push(key.value);
+      }
+   }
+   Ok(keys)
+}
+
+
+/// Encrypt data for each recipient with its latest published X25519 key
+#[hdk_extern]
+pub fn encrypt_for(input: EncryptForInput) -> ExternResult<Vec<(AgentPubKey, XSalsa20Poly1305EncryptedData)>> {
+   std::panic::set_hook(Box::new(zome_panic_hook));
+   let my_key = ensure_enc_pub_key(())?;
+   let mut res = Vec::new();
+   for recipient in input.recipients {
+      let Some(recipient_key) = get_enc_pub_keys(recipient.clone())?.into_iter().next()
+         else { return zome_error!("Agent {} has not published an encryption key", recipient) };
+      let encrypted = x_25519_x_salsa20_poly1305_encrypt(my_key.clone(), recipient_key, input.data.clone())?;
+      res.push((recipient, encrypted));
+   }
+   Ok(res)
+}
+
+
+/// Decrypt data encrypted for me by another agent with encrypt_for()
+#[hdk_extern]
+pub fn decrypt_from(input: DecryptFromInput) -> ExternResult<XSalsa20Poly1305Data> {
+   std::panic::set_hook(Box::new(zome_panic_hook));
+   let sender_keys = get_enc_pub_keys(input.sender)?;
+   for my_key in get_my_enc_pub_keys()? {
+      for sender_key in &sender_keys {
+         let maybe_data = x_25519_x_salsa20_poly1305_decrypt(my_key.clone(), sender_key.clone(), input.data.clone())?;
+         if let Some(data) = maybe_data {
+            return Ok(data);
+         }
+      }
+   }
+   error("Failed to decrypt data from sender")
+}
+
+
+/// Return my X25519 public keys, newest first
+pub fn get_my_enc_pub_keys() -> ExternResult<Vec<X25519PubKey>> {
+   let tuples = get_all_typed_local::<EncPubKey>(FilesEntryTypes::EncPubKey.try_into().unwrap())?;
+   let keys = tuples.into_iter()
+      .rev()
+      .map(|(_, _, key)| key.value)
+      .collect();
+   Ok(keys)
+}
@@ -14,6 +14,7 @@ mod send_file;
 mod get_unreplied_notices;
 mod publish_file_manifest;
 mod utils;
+mod tagging;
 mod probe_public_files;
 mod write_file_chunk;
 mod respond_to_file_notice;
@@ -21,3 +22,19 @@ mod get_file_info;
 mod attach_to_hrl;
 mod get_any_record;
 mod encrypt;
+mod delete_private_file;
+mod unpublish_file;
+mod find_file_by_data_hash;
+mod publish_new_version;
+mod private_folders;
+mod public_folders;
+mod rename_file;
+mod publish_private_file;
+mod signals;
+mod save_public_file;
+mod verify_file;
+mod read_file;
+mod key_directory;
+mod encrypted_distribution;
+mod reencrypt;
+mod enc_key_backup;
@@ -0,0 +1,214 @@
+use hdk::prelude::*;
+use zome_utils::*;
+
+use zome_delivery_types::*;
+use zome_files_integrity::*;
+use crate::get_files::get_private_files;
+use crate::utils::query_deleted_ahs;
+
+
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CreateFolderInput {
+    pub name: String,
+    pub parent: Option<EntryHash>,
+}
+
+
+/// No folder means the root.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MoveFileInput {
+    pub manifest_eh: EntryHash,
+    pub folder_eh: Option<EntryHash>,
+}
+
+
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FolderContent {
+    pub folders: Vec<(EntryHash, PrivateFolder)>,
+    pub files: Vec<(EntryHash, ParcelManifest)>,
+}
+
+
+/// The root has no folder_eh and an empty name.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FolderTree {
+    pub folder_eh: Option<EntryHash>,
+    pub name: String,
+    pub files: Vec<EntryHash>,
+    pub children: Vec<FolderTree>,
+}
+
+
+/// Create a private folder, at the root or in another private folder
+#[hdk_extern]
+pub fn create_folder(input: CreateFolderInput) -> ExternResult<EntryHash> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    if input.name.trim().is_empty() {
+        return error("Folder name is empty");
+    }
+    let folders = query_private_folders()?;
+    if let Some(parent_eh) = &input.parent {
+        if !folders.iter().any(|(eh, _)| eh == parent_eh) {
+            return error("Parent folder not found");
+        }
+    }
+    if folders.iter().any(|(_, folder)| folder.parent == input.parent && folder.name == input.name) {
+        return error("Folder already exists");
+    }
+    /// Create Entry
+    let folder = PrivateFolder { name: input.name, parent: input.parent };
+    let eh = hash_entry(folder.clone())?;
+    let _ah = create_entry(FilesEntry::PrivateFolder(folder))?;
+    /// Done
+    Ok(eh)
+}
+
+
+/// Move one of my private Files to a private folder, or to the root
+#[hdk_extern]
+pub fn move_file_to_folder(input: MoveFileInput) -> ExternResult<()> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    /// Make sure File and folder exist
+    let has_file = get_private_files(())?
+        .iter()
+        .any(|(eh, _)| eh == &input.manifest_eh);
+    if !has_file {
+        return error("Private File not found");
+    }
+    if let Some(folder_eh) = &input.folder_eh {
+        if !query_private_folders()?.iter().any(|(eh, _)| eh == folder_eh) {
+            return error("Folder not found");
+        }
+    }
+    /// Remove from current folder
+    remove_file_from_folders(&input.manifest_eh)?;
+    /// Add to new folder
+    if let Some(folder_eh) = input.folder_eh {
+        let item = PrivateFolderItem { folder_eh, manifest_eh: input.manifest_eh };
+        let _ah = create_entry(FilesEntry::PrivateFolderItem(item))?;
+    }
+    /// Done
+    Ok(())
+}
+
+
+/// List the folders and Files directly in a private folder, or in the root
+#[hdk_extern]
+pub fn list_folder(folder_eh: Option<EntryHash>) -> ExternResult<FolderContent> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let folders = query_private_folders()?;
+    if let Some(eh) = &folder_eh {
+        if !folders.iter().any(|(cur, _)| cur == eh) {
+            return error("Folder not found");
+        }
+    }
+    let files = locate_private_files(&folders)?
+        .into_iter()
+        .filter(|(_, _, location)| location == &folder_eh)
+        .map(|(eh, manifest, _)| (eh, manifest))
+        .collect();
+    let folders = folders.into_iter()
+        .filter(|(_, folder)| folder.parent == folder_eh)
+        .collect();
+    Ok(FolderContent { folders, files })
+}
+
+
+/// Return the tree of folders and Files under a private folder, or under the root
+#[hdk_extern]
+pub fn get_folder_tree(folder_eh: Option<EntryHash>) -> ExternResult<FolderTree> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let folders = query_private_folders()?;
+    let name = match &folder_eh {
+        None => String::new(),
+        Some(eh) => {
+            let Some((_, folder)) = folders.iter().find(|(cur, _)| cur == eh)
+                else { return error("Folder not found") };
+            folder.name.clone()
+        },
+    };
+    let files: Vec<(EntryHash, Option<EntryHash>)> = locate_private_files(&folders)?
+        .into_iter()
+        .map(|(eh, _, location)| (eh, location))
+        .collect();
+    Ok(build_tree(folder_eh, name, &folders, &files))
+}
+
+
+///
+fn build_tree(folder_eh: Option<EntryHash>, name: String, folders: &[(EntryHash, PrivateFolder)], files: &[(EntryHash, Option<EntryHash>)]) -> FolderTree {
+    let children = folders.iter()
+        .filter(|(_, folder)| folder.parent == folder_eh)
+        .map(|(eh, folder)| build_tree(Some(eh.clone()), folder.name.clone(), folders, files))
+        .collect();
+    let files = files.iter()
+        .filter(|(_, location)| location == &folder_eh)
+        .map(|(eh, _)| eh.clone())
+        .collect();
+    FolderTree { folder_eh, name, files, children }
+}
+
+
+/// Return all my private Files with the folder they are in
+fn locate_private_files(folders: &[(EntryHash, PrivateFolder)]) -> ExternResult<Vec<(EntryHash, ParcelManifest, Option<EntryHash>)>> {
+    let items = query_private_folder_items()?;
+    let res = get_private_files(())?
+        .into_iter()
+        .map(|(eh, manifest)| {
+            let location = items.iter()
+                .find(|(_, item)| item.manifest_eh == eh && folders.iter().any(|(folder_eh, _)| folder_eh == &item.folder_eh))
+                .map(|(_, item)| item.folder_eh.clone());
+            (eh, manifest, location)
+        })
+        .collect();
+    Ok(res)
+}
+
+
+/// Remove a File from any private folder it is in
+pub fn remove_file_from_folders(manifest_eh: &EntryHash) -> ExternResult<()> {
+    for (ah, item) in query_private_folder_items()? {
+        if &item.manifest_eh == manifest_eh {
+            let _ = delete_entry(ah)?;
+        }
+    }
+    Ok(())
+}
+
+
+/// Make the folder placement of a File follow to another File
+pub fn move_folder_placement(from_eh: &EntryHash, to_eh: &EntryHash) -> ExternResult<()> {
+    for (ah, item) in query_private_folder_items()? {
+        if &item.manifest_eh != from_eh {
+            continue;
+        }
+        let moved = PrivateFolderItem { manifest_eh: to_eh.clone(), ..item };
+        let _ = create_entry(FilesEntry::PrivateFolderItem(moved))?;
+        let _ = delete_entry(ah)?;
+    }
+    Ok(())
+}
+
+
+/// Return all PrivateFolders on my source-chain
+fn query_private_folders() -> ExternResult<Vec<(EntryHash, PrivateFolder)>> {
+    let tuples = get_all_typed_local::<PrivateFolder>(FilesEntryTypes::PrivateFolder.try_into().unwrap())?;
+    let res = tuples.into_iter()
+        .map(|(_, create, folder)| (create.entry_hash, folder))
+        .collect();
+    Ok(res)
+}
+
+
+/// Return all PrivateFolderItems on my source-chain that have not been deleted
+fn query_private_folder_items() -> ExternResult<Vec<(ActionHash, PrivateFolderItem)>> {
+    let tuples = get_all_typed_local::<PrivateFolderItem>(FilesEntryTypes::PrivateFolderItem.try_into().unwrap())?;
+    let deleted_ahs = query_deleted_ahs()?;
+    let res = tuples.into_iter()
+        .filter(|(ah, _, _)| !deleted_ahs.contains(ah))
+        .map(|(ah, _, item)| (ah, item))
+        .collect();
+    Ok(res)
+}
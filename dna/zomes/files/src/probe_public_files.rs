@@ -3,10 +3,12 @@ use zome_utils::*;
 
 use zome_delivery_types::*;
 use zome_delivery_api::*;
+use zome_files_integrity::is_encrypted_file;
 use crate::utils::ensure_parcel_is_file;
 
 
-/// Wrapper for pull_public_parcels()
+/// Wrapper for pull_public_parcels().
+/// Unpublished Files are not listed since unpublish_file() deletes their public parcel link.
 #[hdk_extern]
 pub fn probe_public_files(_:()) -> ExternResult<Vec<(ParcelReference, Timestamp, AgentPubKey)>> {
     std::panic::set_hook(Box::new(zome_panic_hook));
@@ -14,11 +16,16 @@ pub fn probe_public_files(_:()) -> ExternResult<Vec<(ParcelReference, Timestamp,
     let prs: Vec<(ParcelReference, Timestamp, AgentPubKey)> = decode_response(response)?;
     debug!("files found: {}", prs.len());
     let mut file_manifests = Vec::new();
-    for (pr, ts, author) in &prs {
+    for (pr, ts, author) in prs {
         ///Make sure manifest exists and is of File type.
-        if let Ok(()) = ensure_parcel_is_file(&pr.description) {
-            file_manifests.push((pr.clone(), ts, author));
+        if let Err(_) = ensure_parcel_is_file(&pr.description) {
+            continue;
         }
+        /// Hide encrypted Files, they are only for their recipients
+        if is_encrypted_file(&pr.description) {
+            continue;
+        }
+        file_manifests.push((pr, ts, author));
     }
-    Ok(prs)
+    Ok(file_manifests)
 }
@@ -0,0 +1,104 @@
+use hdk::prelude::*;
+use zome_utils::*;
+
+use zome_delivery_types::*;
+use zome_files_integrity::*;
+use crate::unpublish_file::is_unpublished;
+use crate::utils::ensure_parcel_is_file;
+
+
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AddPublicFileInput {
+    pub path: String,
+    pub manifest_eh: EntryHash,
+}
+
+
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PublicFolderContent {
+    pub folders: Vec<String>,
+    pub files: Vec<(EntryHash, ParcelDescription, AgentPubKey)>,
+}
+
+
+/// Create a public folder and its parents. Path is of the form "Design/2026/Q3".
+/// Return the folder's path EntryHash.
+#[hdk_extern]
+pub fn create_public_folder(path: String) -> ExternResult<EntryHash> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let tp = folder_path(&path)?;
+    tp.ensure()?;
+    Ok(tp.path_entry_hash()?)
+}
+
+
+/// Add a public File to a public folder, creating the folder if necessary
+#[hdk_extern]
+pub fn add_public_file_to_folder(input: AddPublicFileInput) -> ExternResult<ActionHash> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    /// Make sure manifest is a public File
+    let manifest: ParcelManifest = get_typed_from_eh(input.manifest_eh.clone())?;
+    ensure_parcel_is_file(&manifest.description)?;
+    if manifest.description.visibility != EntryVisibility::Public {
+        return error("Can only add a public File to a public folder");
+    }
+    /// Create Path
+    let tp = folder_path(&input.path)?;
+    tp.ensure()?;
+    /// Make sure File is not already in folder
+    let links = get_links(tp.path_entry_hash()?, FilesLinkTypes::PublicFolderFile, None)?;
+    if let Some(link) = links.into_iter().find(|link| link.target.clone().into_entry_hash() == Some(input.manifest_eh.clone())) {
+        return Ok(link.create_link_hash);
+    }
+    /// Create link
+    let tag = PublicFolderTag::new(input.path).to_link_tag()?;
+    let ah = create_link(tp.path_entry_hash()?, input.manifest_eh, FilesLinkTypes::PublicFolderFile, tag)?;
+    /// Done
+    Ok(ah)
+}
+
+
+/// Return the sub-folders' names and the Files of a public folder. An empty path is the root.
+#[hdk_extern]
+pub fn browse_public_folder(path: String) -> ExternResult<PublicFolderContent> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let tp = folder_path(&path)?;
+    /// Grab sub-folders
+    let mut folders = Vec::new();
+    for child in tp_children_paths(&tp)? {
+        let Some(comp) = child.leaf()
+            else { return error("No leaf found for public folder")};
+        let name = String::try_from(comp)
+            .map_err(|e|wasm_error!(SerializedBytesError::Deserialize(e.to_string())))?;
+        folders.push(name);
+    }
+    /// Grab Files
+    let links = get_links(tp.path_entry_hash()?, FilesLinkTypes::PublicFolderFile, None)?;
+    let mut files: Vec<(EntryHash, ParcelDescription, AgentPubKey)> = Vec::new();
+    for link in links {
+        let Some(eh) = link.target.into_entry_hash()
+            else { continue };
+        if files.iter().any(|(cur, _, _)| cur == &eh) || is_unpublished(eh.clone())? {
+            continue;
+        }
+        let Ok(manifest) = get_typed_from_eh::<ParcelManifest>(eh.clone())
+            else { continue };
+        files.push((eh, manifest.description, link.author));
+    }
+    /// Done
+    Ok(PublicFolderContent { folders, files })
+}
+
+
+///
+fn folder_path(path: &str) -> ExternResult<TypedPath> {
+    let names = match parse_folder_path(path) {
+        Ok(names) => names,
+        Err(reason) => return error(&reason),
+    };
+    let tp = public_folder_path(&names)
+        .typed(FilesLinkTypes::PublicFolderPath)?;
+    Ok(tp)
+}
@@ -2,30 +2,25 @@ use hdk::prelude::*;
 use zome_utils::*;
 
 use zome_delivery_types::*;
-use zome_delivery_api::*;
-use zome_files_integrity::{FILES_DEFAULT_INTEGRITY_ZOME_NAME, FILE_TYPE_NAME};
-use crate::commit_private_file::WriteManifestInput;
+use crate::commit_private_file::*;
+use crate::find_file_by_data_hash::find_file;
 
 
 /// Public equivalent of commit_private_file()
+/// Return true as third value if one of my public Files with the same data hash is returned instead.
+/// Otherwise the last value is a File with the same data hash published by another agent, if any.
 #[hdk_extern]
-pub fn publish_file_manifest(input: WriteManifestInput) -> ExternResult<(EntryHash, ParcelDescription)> {
+pub fn publish_file_manifest(input: WriteManifestInput) -> ExternResult<(EntryHash, ParcelDescription, bool, Option<EntryHash>)> {
     std::panic::set_hook(Box::new(zome_panic_hook));
-    let description = ParcelDescription {
-        name: input.filename,
-        size: input.orig_filesize,
-        zome_origin: FILES_DEFAULT_INTEGRITY_ZOME_NAME.into(),
-        visibility: EntryVisibility::Public,
-        kind_info: ParcelKind::Manifest(format!("{}::{}", FILE_TYPE_NAME, input.filetype)),
-    };
+    /// Check if File is already published
+    let maybe_found = find_file(&input.data_hash.to_string(), false)?;
+    if let Some((eh, manifest, true)) = &maybe_found {
+        return Ok((eh.to_owned(), manifest.description.to_owned(), true, None));
+    }
     /// Commit Manifest
-    let manifest = ParcelManifest {
-        data_hash: input.data_hash,
-        chunks: input.chunks,
-        description: description.clone(),
-    };
-    let response = call_delivery_zome("publish_manifest", manifest)?;
-    let eh: EntryHash = decode_response(response)?;
+    let manifest = form_file_manifest(input, EntryVisibility::Public)?;
+    let description = manifest.description.clone();
+    let eh = commit_file_manifest(manifest)?;
     /// Done
-    return Ok((eh, description));
+    return Ok((eh, description, false, maybe_found.map(|(eh, _, _)| eh)));
 }
@@ -0,0 +1,131 @@
+use hdk::prelude::*;
+use zome_utils::*;
+
+use zome_delivery_types::*;
+use zome_files_integrity::*;
+use crate::attach_to_hrl::move_attachments;
+use crate::commit_private_file::{commit_file_manifest, form_file_manifest, WriteManifestInput};
+use crate::get_any_record::get_ah;
+use crate::tagging::copy_tags;
+use crate::unpublish_file::is_unpublished;
+use crate::utils::*;
+
+
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct NewVersionInput {
+    pub previous_manifest_eh: EntryHash,
+    pub input: WriteManifestInput,
+}
+
+
+/// Publish a File as the new version of one of my public Files.
+/// Tags and HRL attachments of the previous version follow to the new one.
+#[hdk_extern]
+pub fn publish_new_version(input: NewVersionInput) -> ExternResult<(EntryHash, ParcelDescription)> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let previous_eh = input.previous_manifest_eh;
+    /// Make sure previous version is one of my public Files
+    let previous: ParcelManifest = get_typed_from_eh(previous_eh.clone())?;
+    ensure_parcel_is_file(&previous.description)?;
+    if previous.description.visibility != EntryVisibility::Public {
+        return error("Previous version is not public");
+    }
+    if query_create_ahs(&[previous_eh.clone()])?.is_empty() {
+        return error("Only the File's author can make a new version of it");
+    }
+    let Some(previous_ah) = get_ah(previous_eh.clone())?
+        else { return error("Previous version record not found") };
+    /// Make sure previous version is the latest
+    let history = get_version_history(previous_eh.clone())?;
+    if history.last() != Some(&previous_eh) {
+        return error("Previous version is not the latest version");
+    }
+    /// Publish new version. No data hash deduplication: each version has its own manifest.
+    let manifest = form_file_manifest(input.input, EntryVisibility::Public)?;
+    let description = manifest.description.clone();
+    let eh = commit_file_manifest(manifest)?;
+    if history.contains(&eh) {
+        return error("File is already a version in this history");
+    }
+    link_new_version(previous_eh, previous_ah, eh.clone(), &description)?;
+    /// Done
+    Ok((eh, description))
+}
+
+
+/// Link a public File to its previous version and make tags and attachments follow
+pub fn link_new_version(previous_eh: EntryHash, previous_ah: ActionHash, eh: EntryHash, description: &ParcelDescription) -> ExternResult<()> {
+    /// Link versions
+    let tag = VersionTag::new(previous_ah).to_link_tag()?;
+    let _ = create_link(previous_eh.clone(), eh.clone(), FilesLinkTypes::NextVersion, tag.clone())?;
+    let _ = create_link(eh.clone(), previous_eh.clone(), FilesLinkTypes::PreviousVersion, tag)?;
+    /// Tags and attachments follow
+    copy_tags(previous_eh.clone(), true, eh.clone(), true, description.name.clone())?;
+    move_attachments(previous_eh, eh, true)?;
+    Ok(())
+}
+
+
+/// Return all published versions of a File, from oldest to newest
+#[hdk_extern]
+pub fn get_file_versions(eh: EntryHash) -> ExternResult<Vec<(EntryHash, ParcelDescription)>> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let mut res = Vec::new();
+    for version_eh in get_version_history(eh)? {
+        if is_unpublished(version_eh.clone())? {
+            continue;
+        }
+        let manifest: ParcelManifest = get_typed_from_eh(version_eh.clone())?;
+        res.push((version_eh, manifest.description));
+    }
+    Ok(res)
+}
+
+
+/// Return the newest published version of a File
+#[hdk_extern]
+pub fn get_latest_version(eh: EntryHash) -> ExternResult<EntryHash> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let versions = get_file_versions(eh.clone())?;
+    let Some((latest_eh, _)) = versions.last()
+        else { return Ok(eh) };
+    Ok(latest_eh.to_owned())
+}
+
+
+/// Return the EntryHashes of a version history, from oldest to newest.
+/// When a version has many links, the most recent one is followed.
+pub fn get_version_history(eh: EntryHash) -> ExternResult<Vec<EntryHash>> {
+    /// Walk back to the first version
+    let mut history = vec![eh.clone()];
+    let mut current = eh.clone();
+    while let Some(previous_eh) = follow_version_link(current, FilesLinkTypes::PreviousVersion)? {
+        if history.contains(&previous_eh) {
+            break;
+        }
+        history.insert(0, previous_eh.clone());
+        current = previous_eh;
+    }
+    /// Walk forward to the latest version
+    let mut current = eh;
+    while let Some(next_eh) = follow_version_link(current, FilesLinkTypes::NextVersion)? {
+        if history.contains(&next_eh) {
+            break;
+        }
+        history.push(next_eh.clone());
+        current = next_eh;
+    }
+    /// Done
+    Ok(history)
+}
+
+
+///
+fn follow_version_link(eh: EntryHash, link_type: FilesLinkTypes) -> ExternResult<Option<EntryHash>> {
+    let links = get_links(eh, link_type, None)?;
+    let maybe_eh = links.into_iter()
+        .max_by_key(|link| link.timestamp)
+        .and_then(|link| link.target.into_entry_hash());
+    Ok(maybe_eh)
+}
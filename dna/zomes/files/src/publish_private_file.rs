@@ -0,0 +1,75 @@
+use hdk::prelude::*;
+use zome_utils::*;
+
+use zome_delivery_types::*;
+use zome_files_integrity::*;
+use crate::attach_to_hrl::move_attachments;
+use crate::commit_private_file::commit_file_manifest;
+use crate::delete_private_file::*;
+use crate::find_file_by_data_hash::find_file;
+use crate::tagging::copy_tags;
+use crate::utils::*;
+use crate::write_file_chunk::write_public_file_chunks;
+
+
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PublishPrivateFileInput {
+    pub manifest_eh: EntryHash,
+    pub keep_private_copy: bool,
+}
+
+
+/// Publish one of my private Files by copying its chunks and manifest to public entries.
+/// If the private copy is not kept, its tags and attachments follow to the public File and become public.
+/// Return true as last value if one of my public Files with the same data hash is returned instead.
+#[hdk_extern]
+pub fn publish_private_file(input: PublishPrivateFileInput) -> ExternResult<(EntryHash, ParcelDescription, bool)> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let private_eh = input.manifest_eh;
+    /// Make sure manifest is one of my private Files
+    let manifest: ParcelManifest = get_typed_from_eh(private_eh.clone())?;
+    ensure_parcel_is_file(&manifest.description)?;
+    if manifest.description.visibility != EntryVisibility::Private {
+        return error("File is not private");
+    }
+    if query_create_ahs(&[private_eh.clone()])?.is_empty() {
+        return error("File not found on source-chain");
+    }
+    if !input.keep_private_copy {
+        ensure_no_distribution_in_progress(&private_eh)?;
+    }
+    /// Publish, unless already published
+    let (public_eh, description, is_dedup) = match find_file(&manifest.data_hash, false)? {
+        Some((eh, public_manifest, true)) => (eh, public_manifest.description, true),
+        _ => {
+            let chunk_ehs = if get_inline_content(&manifest.description).is_some() {
+                Vec::new()
+            } else {
+                let mut chunks = get_file_chunks(&manifest)?;
+                if is_encrypted_file(&manifest.description) {
+                    chunks = resplit_chunks(chunks, get_properties()?.max_chunk_size as usize);
+                }
+                write_public_file_chunks(chunks)?
+            };
+            let description = ParcelDescription {
+                visibility: EntryVisibility::Public,
+                ..without_encryption(&manifest.description)
+            };
+            let public_manifest = ParcelManifest {
+                description: description.clone(),
+                chunks: chunk_ehs,
+                data_hash: manifest.data_hash.clone(),
+            };
+            (commit_file_manifest(public_manifest)?, description, false)
+        },
+    };
+    /// Remove private copy
+    if !input.keep_private_copy {
+        copy_tags(private_eh.clone(), false, public_eh.clone(), true, description.name.clone())?;
+        move_attachments(private_eh.clone(), public_eh.clone(), true)?;
+        delete_private_file(private_eh)?;
+    }
+    /// Done
+    Ok((public_eh, description, is_dedup))
+}
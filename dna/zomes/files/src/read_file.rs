@@ -0,0 +1,251 @@
+use hdk::prelude::*;
+use zome_utils::*;
+use base64::Engine;
+use serde_bytes::ByteBuf;
+
+use zome_delivery_types::*;
+use zome_files_integrity::*;
+use crate::encrypt::{encrypted_chunk_size, get_file_description, get_file_key};
+use crate::utils::*;
+
+
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReadRangeInput {
+    pub manifest_eh: EntryHash,
+    pub offset: u64,
+    pub length: u64,
+}
+
+
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReadChunkInput {
+    pub manifest_eh: EntryHash,
+    pub index: u32,
+}
+
+
+/// Return the base64 content of one of a File's chunks, decrypted if necessary.
+/// A File without chunks, e.g. an inline File, has its whole content as chunk 0.
+/// This is how the UI reads Files: chunks read from the delivery zome can be encrypted, and inline Files have none.
+#[hdk_extern]
+pub fn read_file_chunk(input: ReadChunkInput) -> ExternResult<String> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let manifest: ParcelManifest = get_typed_from_eh(input.manifest_eh)?;
+    ensure_parcel_is_file(&manifest.description)?;
+    if manifest.chunks.is_empty() {
+        if input.index != 0 {
+            return error("Chunk index is out of range");
+        }
+        return Ok(get_inline_content(&manifest.description).unwrap_or_default());
+    }
+    let Some(chunk_eh) = manifest.chunks.get(input.index as usize)
+        else { return error("Chunk index is out of range") };
+    let maybe_key = get_file_key(&manifest)?;
+    Ok(get_chunk(chunk_eh, maybe_key.as_ref())?.data)
+}
+
+
+/// Return a byte range of a File, fetching only the chunks it spans.
+/// Bytes are returned as msgpack binary, i.e. a Uint8Array on the JS side.
+/// The range is truncated at the end of the File.
+#[hdk_extern]
+pub fn read_file_range(input: ReadRangeInput) -> ExternResult<ByteBuf> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let manifest: ParcelManifest = get_typed_from_eh(input.manifest_eh)?;
+    ensure_parcel_is_file(&manifest.description)?;
+    let size = get_file_description(&manifest)?.size;
+    if input.offset > size {
+        return error("Offset is past the end of the File");
+    }
+    let end = size.min(input.offset.saturating_add(input.length));
+    if end == input.offset {
+        return Ok(ByteBuf::new());
+    }
+    /// Inline content is decoded whole
+    if let Some(content) = get_inline_content(&manifest.description) {
+        let bytes = decode_content(&content)?;
+        let Some(range) = bytes.get(input.offset as usize..end as usize)
+            else { return error("Inline content is shorter than the File size") };
+        return Ok(ByteBuf::from(range));
+    }
+    let chunk_size = get_chunk_size(&manifest, size)?;
+    let Some(span) = range_to_chunk_span(size, chunk_size, input.offset, input.length)
+        else { return Ok(ByteBuf::new()) };
+    /// Fetch chunks
+    let maybe_key = get_file_key(&manifest)?;
+    let mut b64 = String::new();
+    for i in span.first..=span.last {
+        let chunk = get_chunk(&manifest.chunks[i as usize], maybe_key.as_ref())?;
+        if i < span.last && chunk.data.len() as u64 != chunk_size {
+            return zome_error!("Chunk {} does not have the expected size", i);
+        }
+        b64.push_str(&chunk.data);
+    }
+    /// Decode
+    let local_end = b64.len().min(span.end_char as usize);
+    let Some(slice) = b64.get(span.start_char as usize..local_end)
+        else { return error("File chunks are shorter than the File size") };
+    let decoded = decode_content(slice)?;
+    let Some(bytes) = decoded.get(span.skip as usize..(span.skip + span.take) as usize)
+        else { return error("File chunks are shorter than the File size") };
+    /// Done
+    Ok(ByteBuf::from(bytes))
+}
+
+
+/// Return the whole content of a File in one call.
+/// Fails if the File is bigger than the maxFileBytesSize DNA property.
+/// Bytes are returned as msgpack binary, i.e. a Uint8Array on the JS side.
+#[hdk_extern]
+pub fn get_file_bytes(manifest_eh: EntryHash) -> ExternResult<ByteBuf> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let manifest: ParcelManifest = get_typed_from_eh(manifest_eh)?;
+    ensure_parcel_is_file(&manifest.description)?;
+    let size = get_file_description(&manifest)?.size;
+    let max_size = get_properties()?.max_file_bytes_size;
+    if size > max_size {
+        return zome_error!("File is too big to be returned whole ({} > {} bytes). Use read_file_range() instead.", size, max_size);
+    }
+    let b64: String = get_file_chunks(&manifest)?
+        .into_iter()
+        .map(|chunk| chunk.data)
+        .collect();
+    Ok(ByteBuf::from(decode_content(&b64)?))
+}
+
+
+///
+pub fn decode_content(b64: &str) -> ExternResult<Vec<u8>> {
+    base64::engine::general_purpose::STANDARD.decode(b64)
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("File content is not base64: {}", e))))
+}
+
+
+/// Chunks of a File holding a byte range, and where the range is in them
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChunkSpan {
+    /// Indices of the first and last chunks to fetch
+    pub first: u64,
+    pub last: u64,
+    /// Base64 chars to decode, within the concatenated chunks
+    pub start_char: u64,
+    pub end_char: u64,
+    /// Bytes of the range, within the decoded chars
+    pub skip: u64,
+    pub take: u64,
+}
+
+
+/// Map a byte range of a File of `size` bytes to its chunks of `chunk_size` base64 chars.
+/// Chunks hold the base64 content of the File: every 3 bytes are 4 chars.
+/// The range is truncated at the end of the File. Return None if it is empty or starts past the end.
+pub fn range_to_chunk_span(size: u64, chunk_size: u64, offset: u64, length: u64) -> Option<ChunkSpan> {
+    let end = size.min(offset.saturating_add(length));
+    if offset >= end {
+        return None;
+    }
+    let b64_len = (size + 2) / 3 * 4;
+    let start_char = offset / 3 * 4;
+    let end_char = b64_len.min((end + 2) / 3 * 4);
+    let first = start_char / chunk_size;
+    let last = (end_char - 1) / chunk_size;
+    Some(ChunkSpan {
+        first,
+        last,
+        start_char: start_char - first * chunk_size,
+        end_char: end_char - first * chunk_size,
+        skip: offset - start_char / 4 * 3,
+        take: end - offset,
+    })
+}
+
+
+/// Length in base64 chars of the chunk at `index` of a File of `size` bytes
+pub fn chunk_b64_len(size: u64, chunk_size: u64, index: u64) -> u64 {
+    let b64_len = (size + 2) / 3 * 4;
+    b64_len.saturating_sub(index * chunk_size).min(chunk_size)
+}
+
+
+/// Return the size of a File's chunks, in base64 chars, checking it against the manifest and the File's size in bytes.
+/// Encrypted Files are split smaller so their encrypted chunks fit in maxChunkSize, see get_encrypted_chunk_size().
+pub fn get_chunk_size(manifest: &ParcelManifest, size: u64) -> ExternResult<u64> {
+    let max_chunk_size = get_properties()?.max_chunk_size as u64;
+    if max_chunk_size == 0 || max_chunk_size % 4 != 0 {
+        return error("maxChunkSize must be a multiple of 4");
+    }
+    let chunk_size = if is_encrypted_file(&manifest.description) {
+        encrypted_chunk_size(max_chunk_size)
+    } else {
+        max_chunk_size
+    };
+    if chunk_size == 0 {
+        return error("maxChunkSize is too small for encrypted chunks");
+    }
+    let b64_len = (size + 2) / 3 * 4;
+    if (b64_len + chunk_size - 1) / chunk_size != manifest.chunks.len() as u64 {
+        return error("File chunks do not match the chunk size");
+    }
+    Ok(chunk_size)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 20 bytes are 28 base64 chars: chunks of 8, 8, 8 and 4 chars, each full chunk holding 6 bytes
+    const SIZE: u64 = 20;
+    const CHUNK_SIZE: u64 = 8;
+
+    #[test]
+    fn empty_range() {
+        assert_eq!(range_to_chunk_span(SIZE, CHUNK_SIZE, 5, 0), None);
+        assert_eq!(range_to_chunk_span(SIZE, CHUNK_SIZE, SIZE, 10), None);
+        assert_eq!(range_to_chunk_span(0, CHUNK_SIZE, 0, 10), None);
+    }
+
+    #[test]
+    fn offset_past_end() {
+        assert_eq!(range_to_chunk_span(SIZE, CHUNK_SIZE, SIZE + 1, 10), None);
+    }
+
+    #[test]
+    fn range_ending_on_chunk_boundary() {
+        let span = range_to_chunk_span(SIZE, CHUNK_SIZE, 0, 6).unwrap();
+        assert_eq!(span, ChunkSpan { first: 0, last: 0, start_char: 0, end_char: 8, skip: 0, take: 6 });
+    }
+
+    #[test]
+    fn range_starting_on_chunk_boundary() {
+        let span = range_to_chunk_span(SIZE, CHUNK_SIZE, 6, 6).unwrap();
+        assert_eq!(span, ChunkSpan { first: 1, last: 1, start_char: 0, end_char: 8, skip: 0, take: 6 });
+    }
+
+    #[test]
+    fn range_across_chunks() {
+        /// Bytes 4..9 are in chars 4..12, which decode to bytes 3..9
+        let span = range_to_chunk_span(SIZE, CHUNK_SIZE, 4, 5).unwrap();
+        assert_eq!(span, ChunkSpan { first: 0, last: 1, start_char: 4, end_char: 12, skip: 1, take: 5 });
+    }
+
+    #[test]
+    fn range_in_last_partial_chunk() {
+        let span = range_to_chunk_span(SIZE, CHUNK_SIZE, 18, 100).unwrap();
+        assert_eq!(span, ChunkSpan { first: 3, last: 3, start_char: 0, end_char: 4, skip: 0, take: 2 });
+    }
+
+    #[test]
+    fn range_truncated_at_end() {
+        let span = range_to_chunk_span(SIZE, CHUNK_SIZE, 0, u64::MAX).unwrap();
+        assert_eq!(span, ChunkSpan { first: 0, last: 3, start_char: 0, end_char: 28, skip: 0, take: SIZE });
+    }
+
+    #[test]
+    fn chunk_lengths() {
+        let lengths: Vec<u64> = (0..5).map(|i| chunk_b64_len(SIZE, CHUNK_SIZE, i)).collect();
+        assert_eq!(lengths, vec![8, 8, 8, 4, 0]);
+    }
+}
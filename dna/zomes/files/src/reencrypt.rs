@@ -0,0 +1,76 @@
+use hdk::prelude::*;
+use zome_utils::*;
+
+use zome_delivery_types::*;
+use zome_files_integrity::*;
+use zome_files_integrity::priv_enc_key::PrivEncKey;
+use crate::delete_private_file::delete_unused_private_chunks;
+use crate::encrypt::*;
+use crate::get_files::get_private_files;
+use crate::rename_file::replace_private_file;
+use crate::utils::*;
+use crate::write_file_chunk::write_private_file_chunks;
+
+
+/// Scheduled by rotate_enc_key().
+/// Re-encrypts one private File to my newest PrivEncKey per run, and reschedules itself until none is left.
+/// Files that fail to be re-encrypted are logged and skipped.
+#[hdk_extern(infallible)]
+fn reencrypt_private_files(_: Option<Schedule>) -> Option<Schedule> {
+    match reencrypt_next_file() {
+        Ok(true) => Some(Schedule::Ephemeral(std::time::Duration::from_secs(1))),
+        Ok(false) => None,
+        Err(e) => {
+            debug!("reencrypt_private_files() failed: {:?}", e);
+            None
+        },
+    }
+}
+
+
+/// Re-encrypt the first private File that has chunks encrypted with an older key and can be re-encrypted.
+/// Return false if there is none.
+fn reencrypt_next_file() -> ExternResult<bool> {
+    let newest = get_my_enc_key()?;
+    for (eh, manifest) in get_private_files(())? {
+        if !is_encrypted_file(&manifest.description) {
+            continue;
+        }
+        match reencrypt_file(&eh, manifest, &newest) {
+            Ok(true) => return Ok(true),
+            Ok(false) => {},
+            Err(e) => debug!("Failed to re-encrypt File {}: {:?}", eh, e),
+        }
+    }
+    Ok(false)
+}
+
+
+/// Re-encrypt a private File if it has chunks encrypted with an older key.
+/// The File is replaced: get_current_private_file() returns its new manifest.
+/// Return false if it is already encrypted with the newest key.
+fn reencrypt_file(eh: &EntryHash, manifest: ParcelManifest, newest: &PrivEncKey) -> ExternResult<bool> {
+    let mut chunks = Vec::with_capacity(manifest.chunks.len());
+    let mut is_outdated = false;
+    for chunk_eh in &manifest.chunks {
+        let chunk = get_typed_from_eh::<ParcelChunk>(chunk_eh.to_owned())?;
+        is_outdated |= get_chunk_key_id(&chunk).unwrap_or(0) != newest.id;
+        chunks.push(decrypt_chunk(chunk, None)?);
+    }
+    if !is_outdated {
+        return Ok(false);
+    }
+    /// Write re-encrypted chunks and replace the File
+    let encrypted = chunks.into_iter()
+        .map(|chunk| encrypt_chunk(chunk, &newest.value, Some(newest.id)))
+        .collect::<ExternResult<Vec<ParcelChunk>>>()?;
+    let chunk_ehs = write_private_file_chunks(encrypted)?;
+    let reencrypted = ParcelManifest {
+        chunks: chunk_ehs,
+        ..manifest.clone()
+    };
+    let previous_ahs = query_create_ahs(&[eh.clone()])?;
+    let _eh = replace_private_file(eh.clone(), previous_ahs, reencrypted)?;
+    delete_unused_private_chunks(eh, manifest.chunks)?;
+    Ok(true)
+}
@@ -0,0 +1,137 @@
+use hdk::prelude::*;
+use zome_utils::*;
+
+use zome_delivery_types::*;
+use zome_files_integrity::*;
+use crate::attach_to_hrl::move_attachments;
+use crate::commit_private_file::commit_file_manifest;
+use crate::delete_private_file::ensure_no_distribution_in_progress;
+use crate::get_any_record::get_ah;
+use crate::private_folders::move_folder_placement;
+use crate::publish_new_version::*;
+use crate::signals::*;
+use crate::tagging::*;
+use crate::unpublish_file::{is_unpublished, unpublish_file};
+use crate::utils::*;
+
+
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RenameFileInput {
+    pub manifest_eh: EntryHash,
+    pub new_name: String,
+}
+
+
+/// Rename one of my Files by committing a new manifest that reuses its chunks.
+/// A private File is replaced: its tags, attachments and folder placement follow and the old manifest is deleted.
+/// A public File gets the renamed manifest as its new version, and the manifest with the old name is unpublished.
+#[hdk_extern]
+pub fn rename_file(input: RenameFileInput) -> ExternResult<(EntryHash, ParcelDescription)> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let manifest: ParcelManifest = get_typed_from_eh(input.manifest_eh.clone())?;
+    ensure_parcel_is_file(&manifest.description)?;
+    let manifest_ahs = query_create_ahs(&[input.manifest_eh.clone()])?;
+    if manifest_ahs.is_empty() {
+        return error("Only the File's author can rename it");
+    }
+    /// Check name
+    check_file_name(&input.new_name)?;
+    if input.new_name == manifest.description.name {
+        return error("File already has this name");
+    }
+    /// Form renamed manifest
+    let description = ParcelDescription {
+        name: input.new_name,
+        ..manifest.description.clone()
+    };
+    let renamed = ParcelManifest {
+        description: description.clone(),
+        ..manifest
+    };
+    /// Commit
+    let eh = match description.visibility {
+        EntryVisibility::Public => rename_public_file(input.manifest_eh, renamed)?,
+        EntryVisibility::Private => {
+            ensure_no_distribution_in_progress(&input.manifest_eh)?;
+            replace_private_file(input.manifest_eh, manifest_ahs, renamed)?
+        },
+    };
+    /// Done
+    Ok((eh, description))
+}
+
+
+/// The manifest with the old name is unpublished so the File is not listed twice
+fn rename_public_file(previous_eh: EntryHash, renamed: ParcelManifest) -> ExternResult<EntryHash> {
+    if is_unpublished(previous_eh.clone())? {
+        return error("File has been unpublished");
+    }
+    let Some(previous_ah) = get_ah(previous_eh.clone())?
+        else { return error("File record not found") };
+    if get_version_history(previous_eh.clone())?.last() != Some(&previous_eh) {
+        return error("Only the latest version of a File can be renamed");
+    }
+    let description = renamed.description.clone();
+    let eh = commit_file_manifest(renamed)?;
+    link_new_version(previous_eh.clone(), previous_ah, eh.clone(), &description)?;
+    unpublish_file(previous_eh)?;
+    Ok(eh)
+}
+
+
+/// Replace one of my private Files with a new manifest: tags, attachments and folder placement follow.
+/// Only the old manifest is deleted, its chunks may be shared with the new one.
+/// The replacement is recorded, see get_current_private_file(), and signaled.
+pub fn replace_private_file(previous_eh: EntryHash, previous_ahs: Vec<ActionHash>, manifest: ParcelManifest) -> ExternResult<EntryHash> {
+    let filename = manifest.description.name.clone();
+    let eh = commit_file_manifest(manifest)?;
+    /// Tags, attachments and folder placement follow
+    copy_tags(previous_eh.clone(), false, eh.clone(), false, filename)?;
+    for tag in get_file_tags(previous_eh.clone(), false)? {
+        untag_file(previous_eh.clone(), tag, false)?;
+    }
+    move_attachments(previous_eh.clone(), eh.clone(), false)?;
+    move_folder_placement(&previous_eh, &eh)?;
+    /// Delete old manifest and record replacement
+    delete_parcel_entries(previous_ahs)?;
+    let _ah = create_entry(FilesEntry::ReplacedFile(ReplacedFile { previous_eh: previous_eh.clone(), eh: eh.clone() }))?;
+    emit_signal(FilesSignal::FileReplaced(previous_eh, eh.clone()))?;
+    Ok(eh)
+}
+
+
+/// Return the current manifest of one of my private Files, following its replacements, e.g. after a re-encryption.
+/// Return the given EntryHash if the File has not been replaced.
+#[hdk_extern]
+pub fn get_current_private_file(manifest_eh: EntryHash) -> ExternResult<EntryHash> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let replacements = get_all_typed_local::<ReplacedFile>(FilesEntryTypes::ReplacedFile.try_into().unwrap())?;
+    let mut current = manifest_eh;
+    let mut seen = vec![current.clone()];
+    while let Some((_, _, replaced)) = replacements.iter().find(|(_, _, replaced)| replaced.previous_eh == current) {
+        if seen.contains(&replaced.eh) {
+            break;
+        }
+        current = replaced.eh.clone();
+        seen.push(current.clone());
+    }
+    Ok(current)
+}
+
+
+/// Check a File name against the DNA properties
+fn check_file_name(name: &str) -> ExternResult<()> {
+    if name.trim().is_empty() {
+        return error("File name is empty");
+    }
+    let properties = get_properties()?;
+    let len = name.chars().count();
+    if len < properties.min_parcel_name_length as usize {
+        return error("File name is too short");
+    }
+    if len > properties.max_parcel_name_length as usize {
+        return error("File name is too long");
+    }
+    Ok(())
+}
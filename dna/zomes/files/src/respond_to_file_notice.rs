@@ -21,15 +21,7 @@ pub fn accept_file_share(parcel_eh: EntryHash) -> ExternResult<EntryHash> {
 
 /// Wrapper for Delivery::respond_to_notice()
 pub fn respond_to_file_notice(parcel_eh: EntryHash, has_accepted: bool) -> ExternResult<EntryHash> {
-    let response = call_delivery_zome(
-        "query_DeliveryNotice",
-        DeliveryNoticeQueryField::Parcel(parcel_eh),
-    )?;
-    let notices: Vec<DeliveryNotice> = decode_response(response)?;
-    if notices.len() != 1 {
-        return zome_error!("No Secret found at given EntryHash");
-    }
-    let notice_eh = hash_entry(notices[0].clone())?;
+    let (notice_eh, _notice) = get_notice_of_parcel(parcel_eh)?;
     let input = RespondToNoticeInput {
         notice_eh,
         has_accepted,
@@ -40,3 +32,18 @@ pub fn respond_to_file_notice(parcel_eh: EntryHash, has_accepted: bool) -> Exter
     Ok(eh)
 }
 
+
+
+/// Return the DeliveryNotice I received for a parcel, and its EntryHash
+pub fn get_notice_of_parcel(parcel_eh: EntryHash) -> ExternResult<(EntryHash, DeliveryNotice)> {
+    let response = call_delivery_zome(
+        "query_DeliveryNotice",
+        DeliveryNoticeQueryField::Parcel(parcel_eh),
+    )?;
+    let notices: Vec<DeliveryNotice> = decode_response(response)?;
+    if notices.len() != 1 {
+        return zome_error!("No Secret found at given EntryHash");
+    }
+    let notice_eh = hash_entry(notices[0].clone())?;
+    Ok((notice_eh, notices[0].clone()))
+}
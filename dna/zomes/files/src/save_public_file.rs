@@ -0,0 +1,75 @@
+use hdk::prelude::*;
+use zome_utils::*;
+
+use zome_delivery_types::*;
+use zome_files_integrity::*;
+use crate::commit_private_file::commit_file_manifest;
+use crate::get_files::get_private_files;
+use crate::signals::*;
+use crate::encrypt::{get_file_data_hash, get_file_description, get_file_key};
+use crate::utils::*;
+use crate::write_file_chunk::write_private_file_chunks;
+
+
+/// Store a public File on my source-chain, so it is kept even if its author unpublishes it.
+/// Emits a SaveProgress signal for each chunk fetched from the DHT.
+/// Return true as last value if an existing private File with the same data hash is returned instead.
+#[hdk_extern]
+pub fn save_public_file(manifest_eh: EntryHash) -> ExternResult<(EntryHash, ParcelDescription, bool)> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    /// Make sure manifest is a public File
+    let manifest: ParcelManifest = get_typed_from_eh(manifest_eh.clone())?;
+    ensure_parcel_is_file(&manifest.description)?;
+    if manifest.description.visibility != EntryVisibility::Public {
+        return error("File is not public");
+    }
+    /// Check if File is already stored
+    let file_data_hash = get_file_data_hash(&manifest)?;
+    if let Some((eh, private)) = get_private_files(())?.into_iter().find(|(_, private)| same_data_hash(&private.data_hash, &file_data_hash)) {
+        return Ok((eh, private.description, true));
+    }
+    /// Fetch chunks
+    let maybe_key = get_file_key(&manifest)?;
+    let total = manifest.chunks.len();
+    let mut chunks = Vec::with_capacity(total);
+    for (i, chunk_eh) in manifest.chunks.iter().enumerate() {
+        let Ok(chunk) = get_chunk(chunk_eh, maybe_key.as_ref())
+            else { return zome_error!("Chunk {} not found", i) };
+        chunks.push(chunk);
+        emit_signal(FilesSignal::SaveProgress(manifest_eh.clone(), i + 1, total))?;
+    }
+    let is_inline = get_inline_content(&manifest.description).is_some();
+    if is_inline {
+        chunks = get_file_chunks(&manifest)?;
+    }
+    /// Check data. A data hash that cannot be parsed cannot be checked.
+    match ContentHash::parse(&file_data_hash) {
+        Ok(data_hash) => {
+            if compute_data_hash(&chunks, data_hash.algorithm) != data_hash {
+                return error("File data does not match its data hash");
+            }
+        },
+        Err(reason) => debug!("Data hash of {} not checked: {}", manifest_eh, reason),
+    }
+    /// Commit chunks and manifest
+    /// Encrypted chunks are split smaller than plaintext ones and carry the data hash of the ciphertext
+    if is_encrypted_file(&manifest.description) {
+        chunks = resplit_chunks(chunks, get_properties()?.max_chunk_size as usize)
+            .into_iter()
+            .map(|chunk| ParcelChunk { data_hash: file_data_hash.clone(), ..chunk })
+            .collect();
+    }
+    let chunk_ehs = if is_inline { Vec::new() } else { write_private_file_chunks(chunks)? };
+    let description = ParcelDescription {
+        visibility: EntryVisibility::Private,
+        ..without_encryption(&get_file_description(&manifest)?)
+    };
+    let private = ParcelManifest {
+        description: description.clone(),
+        chunks: chunk_ehs,
+        data_hash: file_data_hash,
+    };
+    let eh = commit_file_manifest(private)?;
+    /// Done
+    Ok((eh, description, false))
+}
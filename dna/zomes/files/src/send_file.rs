@@ -3,6 +3,7 @@ use zome_utils::*;
 
 use zome_delivery_types::*;
 use zome_delivery_api::*;
+use zome_files_integrity::is_encrypted_file;
 use crate::utils::ensure_parcel_is_file;
 
 ///
@@ -14,6 +15,7 @@ pub struct SendFileInput {
 }
 
 /// Wrapper for distribute_parcel()
+/// Inline Files have no chunks, their content is delivered with the manifest.
 /// Return Distribution ActionHash
 #[hdk_extern]
 pub fn send_file(input: SendFileInput) -> ExternResult<ActionHash> {
@@ -26,6 +28,10 @@ pub fn send_file(input: SendFileInput) -> ExternResult<ActionHash> {
     ///Make sure manifest exists and is of File type.
     let manifest: ParcelManifest = get_typed_from_eh(input.manifest_eh.clone())?;
     ensure_parcel_is_file(&manifest.description)?;
+    /// Recipients could not decrypt chunks encrypted with my PrivEncKey
+    if is_encrypted_file(&manifest.description) {
+        return error("An encrypted File cannot be sent");
+    }
 
     /// Form Parcel Reference
     let parcel_reference = ParcelReference {
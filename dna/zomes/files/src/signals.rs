@@ -0,0 +1,12 @@
+use hdk::prelude::*;
+
+
+/// Signals emitted by this zome
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub enum FilesSignal {
+    /// (manifest_eh, fetched chunks, total chunks)
+    SaveProgress(EntryHash, usize, usize),
+    /// (previous manifest_eh, new manifest_eh) of a private File replaced, e.g. re-encrypted in the background
+    FileReplaced(EntryHash, EntryHash),
+}
+
@@ -0,0 +1,87 @@
+use hdk::prelude::*;
+use zome_utils::*;
+
+
+pub const TAGGING_DEFAULT_COORDINATOR_ZOME_NAME: &'static str = "zTagging";
+
+
+/// Input for zTagging's tag_private_entry() & tag_public_entry()
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TaggingInput {
+    pub tags: Vec<String>,
+    pub target: EntryHash,
+    pub link_tag_to_entry: String,
+}
+
+
+/// Input for zTagging's untag_private_entry() & untag_public_entry()
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UntagInput {
+    pub tag: String,
+    pub target: EntryHash,
+}
+
+
+///
+pub fn call_tagging_zome<I>(fn_name: &str, payload: I) -> ExternResult<ZomeCallResponse>
+    where
+        I: serde::Serialize + std::fmt::Debug
+{
+    call(
+        CallTargetCell::Local,
+        TAGGING_DEFAULT_COORDINATOR_ZOME_NAME,
+        fn_name.into(),
+        None,
+        payload,
+    )
+}
+
+
+/// Return the tags of a File, deduplicated
+pub fn get_file_tags(eh: EntryHash, is_public: bool) -> ExternResult<Vec<String>> {
+    let mut tags: Vec<String> =
+        if is_public {
+            let response = call_tagging_zome("get_public_tags", eh)?;
+            decode_response(response)?
+        } else {
+            let response = call_tagging_zome("get_private_tags", eh)?;
+            let pairs: Vec<(EntryHash, String)> = decode_response(response)?;
+            pairs.into_iter().map(|(_, tag)| tag).collect()
+        };
+    tags.sort();
+    tags.dedup();
+    Ok(tags)
+}
+
+
+///
+pub fn tag_file(eh: EntryHash, tags: Vec<String>, filename: String, is_public: bool) -> ExternResult<()> {
+    if tags.is_empty() {
+        return Ok(());
+    }
+    let fn_name = if is_public { "tag_public_entry" } else { "tag_private_entry" };
+    let input = TaggingInput { tags, target: eh, link_tag_to_entry: filename };
+    let response = call_tagging_zome(fn_name, input)?;
+    let _: () = decode_response(response)?;
+    Ok(())
+}
+
+
+/// Does not fail if the tag could not be removed,
+/// e.g. a public tag set only by other agents, since zTagging only removes my own tag links.
+pub fn untag_file(eh: EntryHash, tag: String, is_public: bool) -> ExternResult<()> {
+    let fn_name = if is_public { "untag_public_entry" } else { "untag_private_entry" };
+    match call_tagging_zome(fn_name, UntagInput { tag: tag.clone(), target: eh }) {
+        Ok(ZomeCallResponse::Ok(_)) => {},
+        Ok(response) => debug!("Tag '{}' not removed: {:?}", tag, response),
+        Err(e) => debug!("Tag '{}' not removed: {:?}", tag, e),
+    }
+    Ok(())
+}
+
+
+/// Copy the tags of a File to another File
+pub fn copy_tags(from_eh: EntryHash, from_is_public: bool, to_eh: EntryHash, to_is_public: bool, filename: String) -> ExternResult<()> {
+    let tags = get_file_tags(from_eh, from_is_public)?;
+    tag_file(to_eh, tags, filename, to_is_public)
+}
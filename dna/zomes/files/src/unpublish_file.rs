@@ -0,0 +1,69 @@
+use hdk::prelude::*;
+use zome_utils::*;
+
+use zome_delivery_types::*;
+use zome_delivery_api::*;
+use zome_files_integrity::*;
+use crate::attach_to_hrl::detach_from_all_hrls;
+use crate::utils::*;
+use crate::tagging::*;
+
+
+/// Remove a File I have published: its manifest, its public parcel link, its data hash index, my public tags and its attachments.
+/// Tags set by other agents stay.
+/// Out of scope: validation of the manifest and public parcel link deletes.
+/// Those entries and links belong to the delivery zome, whose integrity crate is an external dependency
+/// that this DNA only re-exports, so the author-only delete rule must be added upstream in delivery-zome.
+/// Until then the rule is checked here and enforced on read: is_unpublished() ignores deletes made by other agents.
+#[hdk_extern]
+pub fn unpublish_file(manifest_eh: EntryHash) -> ExternResult<()> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    /// Make sure manifest is a public File
+    let manifest: ParcelManifest = get_typed_from_eh(manifest_eh.clone())?;
+    ensure_parcel_is_file(&manifest.description)?;
+    if manifest.description.visibility != EntryVisibility::Public {
+        return error("File is not public");
+    }
+    /// Make sure I am the author
+    let manifest_ahs = query_create_ahs(&[manifest_eh.clone()])?;
+    if manifest_ahs.is_empty() {
+        return error("Only the File's author can unpublish it");
+    }
+    /// Remove my public tags. Tags set only by other agents can only be removed by them.
+    for tag in get_file_tags(manifest_eh.clone(), true)? {
+        untag_file(manifest_eh.clone(), tag, true)?;
+    }
+    /// Remove attachments
+    detach_from_all_hrls(manifest_eh.clone())?;
+    /// Remove data hash index
+    let me = agent_info()?.agent_latest_pubkey;
+    let links = get_links(data_hash_path(&manifest.data_hash).path_entry_hash()?, FilesLinkTypes::DataHash, None)?;
+    for link in links {
+        if link.author == me && link.target.clone().into_entry_hash() == Some(manifest_eh.clone()) {
+            let _ = delete_link(link.create_link_hash)?;
+        }
+    }
+    /// Remove public parcel link
+    let response = call_delivery_zome("remove_public_parcel", manifest_eh)?;
+    let _ah: ActionHash = decode_response(response)?;
+    /// Delete manifest
+    delete_parcel_entries(manifest_ahs)?;
+    /// Done
+    Ok(())
+}
+
+
+/// A public File is unpublished when each of its Create actions has been deleted by its author
+pub fn is_unpublished(manifest_eh: EntryHash) -> ExternResult<bool> {
+    let Some(Details::Entry(details)) = get_details(manifest_eh, GetOptions::default())?
+        else { return Ok(false) };
+    let deleted_ahs: Vec<(ActionHash, AgentPubKey)> = details.deletes.iter()
+        .filter_map(|sah| match sah.action() {
+            Action::Delete(delete) => Some((delete.deletes_address.clone(), delete.author.clone())),
+            _ => None,
+        })
+        .collect();
+    let is_unpublished = details.actions.iter()
+        .all(|sah| deleted_ahs.contains(&(sah.action_address().to_owned(), sah.action().author().to_owned())));
+    Ok(is_unpublished)
+}
@@ -2,19 +2,146 @@ use hdk::prelude::*;
 use zome_utils::*;
 
 use zome_delivery_types::*;
-use zome_files_integrity::{FILES_DEFAULT_INTEGRITY_ZOME_NAME, FILE_TYPE_NAME};
+use zome_delivery_api::*;
+use zome_files_integrity::*;
+use crate::encrypt::{decrypt_chunk, get_file_key};
 
 ///
 pub fn ensure_parcel_is_file(description: &ParcelDescription) -> ExternResult<()> {
-    if description.zome_origin != FILES_DEFAULT_INTEGRITY_ZOME_NAME.into() {
-        return error(&format!("Parcel is not from '{}' zome. Zome origin: {}", FILES_DEFAULT_INTEGRITY_ZOME_NAME, description.zome_origin));
+    if let Err(reason) = check_parcel_is_file(description) {
+        return error(&reason);
     }
-    let ParcelKind::Manifest(data_type) = description.kind_info.clone() else {
-        return error("Parcel is not of type Manifest");
-    };
-    if &data_type[..FILE_TYPE_NAME.len()] != FILE_TYPE_NAME {
-        return error(&format!("Parcel is not of type {}", FILE_TYPE_NAME));
+    Ok(())
+}
+
+
+/// Return ActionHashes of all entries deleted on my source-chain
+pub fn query_deleted_ahs() -> ExternResult<Vec<ActionHash>> {
+    let records = query(ChainQueryFilter::new().action_type(ActionType::Delete))?;
+    let ahs = records.into_iter()
+        .filter_map(|record| match record.action() {
+            Action::Delete(delete) => Some(delete.deletes_address.clone()),
+            _ => None,
+        })
+        .collect();
+    Ok(ahs)
+}
+
+
+/// Get links of a type from many bases in a single host call
+pub fn get_links_batch(bases: Vec<EntryHash>, link_type: impl LinkTypeFilterExt) -> ExternResult<Vec<Vec<Link>>> {
+    let filter = link_type.try_into_filter()?;
+    let inputs: Vec<GetLinksInput> = bases.into_iter()
+        .map(|base| GetLinksInput::new(base.into(), filter.clone(), None))
+        .collect();
+    let res = HDK.with(|h| h.borrow().get_links(inputs))?;
+    Ok(res)
+}
+
+
+/// Return (ActionHash, EntryHash) of all Create actions of the given entries on my source-chain that have not been deleted
+pub fn query_live_creates(ehs: &[EntryHash]) -> ExternResult<Vec<(ActionHash, EntryHash)>> {
+    query_live_creates_with_visibility(ehs, None)
+}
+
+
+/// Same as query_live_creates() but only for entries of the given visibility
+pub fn query_live_creates_with_visibility(ehs: &[EntryHash], maybe_visibility: Option<EntryVisibility>) -> ExternResult<Vec<(ActionHash, EntryHash)>> {
+    let deleted_ahs = query_deleted_ahs()?;
+    let records = query(ChainQueryFilter::new().action_type(ActionType::Create))?;
+    let res = records.into_iter()
+        .filter_map(|record| {
+            let (eh, entry_type) = record.action().entry_data()?;
+            if !ehs.contains(eh) || deleted_ahs.contains(record.action_address()) {
+                return None;
+            }
+            if let Some(visibility) = maybe_visibility {
+                if entry_type.visibility() != &visibility {
+                    return None;
+                }
+            }
+            Some((record.action_address().to_owned(), eh.to_owned()))
+        })
+        .collect();
+    Ok(res)
+}
+
+
+/// Return ActionHashes of all Create actions of the given entries on my source-chain that have not been deleted
+pub fn query_create_ahs(ehs: &[EntryHash]) -> ExternResult<Vec<ActionHash>> {
+    let ahs = query_live_creates(ehs)?
+        .into_iter()
+        .map(|(ah, _eh)| ah)
+        .collect();
+    Ok(ahs)
+}
+
+
+/// Delete parcel manifests or chunks through the delivery zome, which owns their entry types
+pub fn delete_parcel_entries(ahs: Vec<ActionHash>) -> ExternResult<()> {
+    if ahs.is_empty() {
+        return Ok(());
     }
+    let response = call_delivery_zome("delete_parcel_entries", ahs)?;
+    let _delete_ahs: Vec<ActionHash> = decode_response(response)?;
     Ok(())
 }
 
+
+
+/// Get all chunks of a File, in order and decrypted.
+/// The content of an inline File is returned as a single chunk.
+pub fn get_file_chunks(manifest: &ParcelManifest) -> ExternResult<Vec<ParcelChunk>> {
+    if let Some(data) = get_inline_content(&manifest.description) {
+        return Ok(vec![ParcelChunk { data_hash: manifest.data_hash.clone(), data }]);
+    }
+    let maybe_key = get_file_key(manifest)?;
+    manifest.chunks.iter()
+        .map(|chunk_eh| get_chunk(chunk_eh, maybe_key.as_ref()))
+        .collect()
+}
+
+
+/// Split the content of chunks again into chunks of `chunk_size` chars.
+/// Needed when copying a File between plaintext and encrypted chunks, as they do not have the same size.
+pub fn resplit_chunks(chunks: Vec<ParcelChunk>, chunk_size: usize) -> Vec<ParcelChunk> {
+    let Some(data_hash) = chunks.first().map(|chunk| chunk.data_hash.clone())
+        else { return chunks };
+    let content: String = chunks.into_iter().map(|chunk| chunk.data).collect();
+    /// Base64 is ASCII so splitting on bytes is safe
+    content.as_bytes()
+        .chunks(chunk_size)
+        .map(|data| ParcelChunk { data_hash: data_hash.clone(), data: String::from_utf8_lossy(data).into_owned() })
+        .collect()
+}
+
+
+/// Get a chunk, from my source-chain or from the DHT, and decrypt it with the File's key if any
+pub fn get_chunk(chunk_eh: &EntryHash, maybe_key: Option<&XSalsa20Poly1305KeyRef>) -> ExternResult<ParcelChunk> {
+    let chunk = get_typed_from_eh::<ParcelChunk>(chunk_eh.to_owned())?;
+    decrypt_chunk(chunk, maybe_key)
+}
+
+
+/// Compute the data hash of a File from its chunks, the same way the UI does:
+/// hash of the whole base64 content.
+pub fn compute_data_hash(chunks: &[ParcelChunk], algorithm: HashAlgorithm) -> ContentHash {
+    let digest = match algorithm {
+        HashAlgorithm::Sha256 => {
+            use sha2::Digest;
+            let mut hasher = sha2::Sha256::new();
+            for chunk in chunks {
+                hasher.update(chunk.data.as_bytes());
+            }
+            hasher.finalize().to_vec()
+        },
+        HashAlgorithm::Blake3 => {
+            let mut hasher = blake3::Hasher::new();
+            for chunk in chunks {
+                hasher.update(chunk.data.as_bytes());
+            }
+            hasher.finalize().as_bytes().to_vec()
+        },
+    };
+    ContentHash::new(algorithm, digest)
+}
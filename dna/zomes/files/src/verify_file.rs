@@ -0,0 +1,72 @@
+use hdk::prelude::*;
+use zome_utils::*;
+
+use zome_delivery_types::*;
+use zome_files_integrity::*;
+use crate::encrypt::{decrypt_chunk, get_file_data_hash, get_file_description, get_file_key};
+use crate::read_file::{chunk_b64_len, decode_content, get_chunk_size};
+use crate::utils::*;
+
+
+///
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FileVerification {
+    pub is_valid: bool,
+    /// None if some chunks are missing or cannot be decrypted
+    pub computed_data_hash: Option<ContentHash>,
+    pub missing_chunks: Vec<usize>,
+    pub corrupt_chunks: Vec<usize>,
+}
+
+
+/// Check a File's chunks against its manifest and recompute its data hash over its whole content.
+/// A chunk is corrupt if it cannot be decrypted, is not base64, or does not have the length its index requires.
+/// Chunks have no digest of their own, so any other alteration only shows as a data hash mismatch:
+/// the File is then invalid without any corrupt chunk.
+/// Works for private, public and received Files.
+#[hdk_extern]
+pub fn verify_file(manifest_eh: EntryHash) -> ExternResult<FileVerification> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let manifest: ParcelManifest = get_typed_from_eh(manifest_eh)?;
+    ensure_parcel_is_file(&manifest.description)?;
+    /// Fetch and check chunks
+    let maybe_key = get_file_key(&manifest)?;
+    /// Chunk lengths can only be checked if the File was split with the current maxChunkSize
+    let size = get_file_description(&manifest)?.size;
+    let maybe_chunk_size = get_chunk_size(&manifest, size).ok();
+    let mut chunks = Vec::with_capacity(manifest.chunks.len());
+    let mut missing_chunks = Vec::new();
+    let mut corrupt_chunks = Vec::new();
+    for (i, chunk_eh) in manifest.chunks.iter().enumerate() {
+        let Ok(chunk) = get_typed_from_eh::<ParcelChunk>(chunk_eh.to_owned())
+            else { missing_chunks.push(i); continue };
+        let Ok(chunk) = decrypt_chunk(chunk, maybe_key.as_ref())
+            else { corrupt_chunks.push(i); continue };
+        let has_expected_len = maybe_chunk_size
+            .map(|chunk_size| chunk.data.len() as u64 == chunk_b64_len(size, chunk_size, i as u64))
+            .unwrap_or(true);
+        if !has_expected_len || decode_content(&chunk.data).is_err() {
+            corrupt_chunks.push(i);
+        }
+        chunks.push(chunk);
+    }
+    let is_inline = get_inline_content(&manifest.description).is_some();
+    if is_inline {
+        chunks = get_file_chunks(&manifest)?;
+    }
+    /// Recompute data hash over the reassembled content, as the UI computed it. Unparsable data hashes are assumed to be SHA-256.
+    let data_hash = get_file_data_hash(&manifest)?;
+    let algorithm = ContentHash::parse(&data_hash)
+        .map(|hash| hash.algorithm)
+        .unwrap_or(HashAlgorithm::Sha256);
+    let computed_data_hash = if is_inline || chunks.len() == manifest.chunks.len() {
+        Some(compute_data_hash(&chunks, algorithm))
+    } else {
+        None
+    };
+    let is_valid = missing_chunks.is_empty()
+        && corrupt_chunks.is_empty()
+        && computed_data_hash.as_ref().is_some_and(|hash| hash.matches(&data_hash));
+    /// Done
+    Ok(FileVerification { is_valid, computed_data_hash, missing_chunks, corrupt_chunks })
+}
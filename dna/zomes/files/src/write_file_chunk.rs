@@ -3,9 +3,11 @@ use zome_utils::*;
 
 use zome_delivery_types::*;
 use zome_delivery_api::*;
+use crate::encrypt::{encrypt_chunk, get_encrypted_chunk_size, get_my_enc_key};
 
 
 /// Wrapper for commit_parcel_chunks()
+/// Deduplication is done per File: find_file_by_data_hash() must be called before writing any chunk.
 #[hdk_extern]
 pub fn write_private_file_chunks(chunks: Vec<ParcelChunk>) -> ExternResult<Vec<EntryHash>> {
     //debug!(" write_private_file_chunks() chunks: {} ({})", chunks.len(), chunks[0].data_hash);
@@ -17,7 +19,26 @@ pub fn write_private_file_chunks(chunks: Vec<ParcelChunk>) -> ExternResult<Vec<E
 }
 
 
+/// Same as write_private_file_chunks() but chunks are encrypted with my PrivEncKey.
+/// The File must be split with get_encrypted_chunk_size() instead of maxChunkSize, so encrypted chunks fit in maxChunkSize.
+/// The manifest must then be committed with `is_encrypted` set.
+#[hdk_extern]
+pub fn write_encrypted_private_file_chunks(chunks: Vec<ParcelChunk>) -> ExternResult<Vec<EntryHash>> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let chunk_size = get_encrypted_chunk_size(())? as usize;
+    if chunks.iter().any(|chunk| chunk.data.len() > chunk_size) {
+        return zome_error!("Chunks to encrypt must not be larger than {} chars", chunk_size);
+    }
+    let key = get_my_enc_key()?;
+    let encrypted = chunks.into_iter()
+        .map(|chunk| encrypt_chunk(chunk, &key.value, Some(key.id)))
+        .collect::<ExternResult<Vec<ParcelChunk>>>()?;
+    write_private_file_chunks(encrypted)
+}
+
+
 /// Wrapper for publish_chunks()
+/// Deduplication is done per File: find_file_by_data_hash() must be called before writing any chunk.
 #[hdk_extern]
 pub fn write_public_file_chunks(chunks: Vec<ParcelChunk>) -> ExternResult<Vec<EntryHash>> {
     //debug!(" write_public_file_chunk() size: {}", chunks.len());
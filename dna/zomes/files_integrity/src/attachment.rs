@@ -0,0 +1,75 @@
+use hdi::prelude::*;
+use zome_utils::*;
+use zome_delivery_types::*;
+use crate::{ATTACHMENTS_ROOT, FILES_DEFAULT_INTEGRITY_ZOME_NAME, FILE_TYPE_NAME};
+
+
+/// LinkTag of an Attachment link.
+/// Holds the HRL so validation can check that the link's base is its HRL path,
+/// and the manifest's ActionHash so validation can find the manifest's author.
+/// An optional label gives context about the attachment.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct AttachmentTag {
+   pub hrl: (DnaHash, EntryHash),
+   pub manifest_ah: ActionHash,
+   pub label: Option<String>,
+}
+
+impl AttachmentTag {
+   pub fn new(hrl: (DnaHash, EntryHash), manifest_ah: ActionHash, label: Option<String>) -> Self {
+      Self {
+         hrl,
+         manifest_ah,
+         label,
+      }
+   }
+
+   ///
+   pub fn to_link_tag(&self) -> ExternResult<LinkTag> {
+      let sb = SerializedBytes::try_from(self.clone())
+         .map_err(|e| wasm_error!(e))?;
+      Ok(LinkTag::new(sb.bytes().to_owned()))
+   }
+
+   ///
+   pub fn from_link_tag(tag: &LinkTag) -> ExternResult<Self> {
+      let sb = SerializedBytes::from(UnsafeBytes::from(tag.0.clone()));
+      let attachment_tag = Self::try_from(sb)
+         .map_err(|e| wasm_error!(e))?;
+      Ok(attachment_tag)
+   }
+}
+
+
+/// Entry representing a File attached to a HRL, known only to its author.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct PrivateAttachment {
+   pub hrl: (DnaHash, EntryHash),
+   pub manifest_eh: EntryHash,
+   pub label: Option<String>,
+}
+
+
+/// Path of a We HRL: "<ATTACHMENTS_ROOT>.<DnaHash>.<EntryHash>"
+pub fn hrl_to_path(hrl: &(DnaHash, EntryHash)) -> Path {
+   let mut path = Path::from(format!("{}", ATTACHMENTS_ROOT));
+   path.append_component(hash2comp(hrl.0.clone()));
+   path.append_component(hash2comp(hrl.1.clone()));
+   path
+}
+
+
+/// Return the reason why the ParcelDescription is not one of a File, if any.
+pub fn check_parcel_is_file(description: &ParcelDescription) -> Result<(), String> {
+   if description.zome_origin != FILES_DEFAULT_INTEGRITY_ZOME_NAME.into() {
+      return Err(format!("Parcel is not from '{}' zome. Zome origin: {}", FILES_DEFAULT_INTEGRITY_ZOME_NAME, description.zome_origin));
+   }
+   let ParcelKind::Manifest(data_type) = &description.kind_info else {
+      return Err("Parcel is not of type Manifest".to_string());
+   };
+   if !data_type.starts_with(FILE_TYPE_NAME) {
+      return Err(format!("Parcel is not of type {}", FILE_TYPE_NAME));
+   }
+   Ok(())
+}
@@ -0,0 +1,164 @@
+use hdi::prelude::*;
+use base64::Engine;
+use crate::DATA_HASH_ROOT;
+
+
+/// Path indexing public Files by data hash: "<DATA_HASH_ROOT>.<data_hash>"
+/// Only the leaf is linked to, so the path tree is never created.
+pub fn data_hash_path(data_hash: &str) -> Path {
+   let mut path = Path::from(format!("{}", DATA_HASH_ROOT));
+   path.append_component(data_hash.to_string().into());
+   path
+}
+
+
+/// Hash algorithms supported for File content. Values are the multihash codes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashAlgorithm {
+   Sha256 = 0x12,
+   Blake3 = 0x1e,
+}
+
+
+/// Hash of a File's content (the whole base64 string), with its algorithm.
+/// Serialized as a string: "u" + base64url of the multihash (code, length, digest).
+/// SHA-256 keeps the legacy encoding (digest only) so existing manifests and paths stay valid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContentHash {
+   pub algorithm: HashAlgorithm,
+   pub digest: Vec<u8>,
+}
+
+impl ContentHash {
+   pub fn new(algorithm: HashAlgorithm, digest: Vec<u8>) -> Self {
+      Self { algorithm, digest }
+   }
+
+   /// Parse a data hash string, in multihash or legacy SHA-256 form
+   pub fn parse(data_hash: &str) -> Result<Self, String> {
+      let Some(encoded) = data_hash.strip_prefix('u')
+         else { return Err(format!("Data hash has no multibase prefix: {}", data_hash)) };
+      let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(encoded)
+         .map_err(|e| format!("Data hash is not base64url: {}", e))?;
+      /// Legacy
+      if bytes.len() == 32 {
+         return Ok(Self::new(HashAlgorithm::Sha256, bytes));
+      }
+      /// Multihash
+      if bytes.len() != 34 || bytes[1] != 32 {
+         return Err(format!("Data hash has an unsupported length: {}", bytes.len()));
+      }
+      let algorithm = match bytes[0] {
+         0x12 => HashAlgorithm::Sha256,
+         0x1e => HashAlgorithm::Blake3,
+         code => return Err(format!("Data hash has an unsupported algorithm: {:#x}", code)),
+      };
+      Ok(Self::new(algorithm, bytes[2..].to_vec()))
+   }
+
+   /// True if the given data hash string is the same hash, whatever its form
+   pub fn matches(&self, data_hash: &str) -> bool {
+      Self::parse(data_hash).as_ref() == Ok(self)
+   }
+}
+
+impl std::fmt::Display for ContentHash {
+   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+      let bytes = match self.algorithm {
+         HashAlgorithm::Sha256 => self.digest.clone(),
+         HashAlgorithm::Blake3 => [vec![self.algorithm as u8, self.digest.len() as u8], self.digest.clone()].concat(),
+      };
+      write!(f, "u{}", base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes))
+   }
+}
+
+impl Serialize for ContentHash {
+   fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+      serializer.serialize_str(&self.to_string())
+   }
+}
+
+impl<'de> Deserialize<'de> for ContentHash {
+   fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+      let data_hash = String::deserialize(deserializer)?;
+      Self::parse(&data_hash).map_err(serde::de::Error::custom)
+   }
+}
+
+
+/// True if both data hash strings are the same hash. Unparsable strings are compared as is.
+pub fn same_data_hash(a: &str, b: &str) -> bool {
+   match ContentHash::parse(a) {
+      Ok(hash) => hash.matches(b),
+      Err(_) => a == b,
+   }
+}
+
+
+#[cfg(test)]
+mod tests {
+   use super::*;
+
+   const DIGEST: [u8; 32] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31];
+   /// "u" + base64url of the digest only, as computed by the UI
+   const LEGACY_SHA256: &str = "uAAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8";
+   const MULTIHASH_SHA256: &str = "uEiAAAQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHw";
+   const MULTIHASH_BLAKE3: &str = "uHiAAAQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHw";
+
+   #[test]
+   fn legacy_sha256_round_trip() {
+      let hash = ContentHash::parse(LEGACY_SHA256).unwrap();
+      assert_eq!(hash, ContentHash::new(HashAlgorithm::Sha256, DIGEST.to_vec()));
+      assert_eq!(hash.to_string(), LEGACY_SHA256);
+   }
+
+   #[test]
+   fn multihash_sha256_displays_as_legacy() {
+      let hash = ContentHash::parse(MULTIHASH_SHA256).unwrap();
+      assert_eq!(hash, ContentHash::new(HashAlgorithm::Sha256, DIGEST.to_vec()));
+      assert_eq!(hash.to_string(), LEGACY_SHA256);
+      assert!(hash.matches(LEGACY_SHA256));
+      assert!(same_data_hash(MULTIHASH_SHA256, LEGACY_SHA256));
+   }
+
+   #[test]
+   fn blake3_round_trip() {
+      let hash = ContentHash::parse(MULTIHASH_BLAKE3).unwrap();
+      assert_eq!(hash, ContentHash::new(HashAlgorithm::Blake3, DIGEST.to_vec()));
+      assert_eq!(hash.to_string(), MULTIHASH_BLAKE3);
+      assert!(!hash.matches(LEGACY_SHA256));
+      assert!(!same_data_hash(MULTIHASH_BLAKE3, MULTIHASH_SHA256));
+   }
+
+   #[test]
+   fn rejects_malformed_hashes() {
+      /// No multibase prefix
+      assert!(ContentHash::parse(&LEGACY_SHA256[1..]).is_err());
+      /// Not base64url
+      assert!(ContentHash::parse("u+/+/").is_err());
+      /// Wrong length
+      assert!(ContentHash::parse("uAAEC").is_err());
+      /// Unknown algorithm
+      assert!(ContentHash::parse("uEyAAAQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHw").is_err());
+   }
+
+   #[test]
+   fn unparsable_hashes_are_compared_as_is() {
+      assert!(same_data_hash("not-a-hash", "not-a-hash"));
+      assert!(!same_data_hash("not-a-hash", LEGACY_SHA256));
+      assert!(!same_data_hash(LEGACY_SHA256, "not-a-hash"));
+   }
+
+   #[test]
+   fn serde_round_trip() {
+      for data_hash in [LEGACY_SHA256, MULTIHASH_BLAKE3] {
+         let hash = ContentHash::parse(data_hash).unwrap();
+         let io = ExternIO::encode(hash.clone()).unwrap();
+         /// Serialized as a plain string
+         let as_string: String = io.decode().unwrap();
+         assert_eq!(as_string, data_hash);
+         let decoded: ContentHash = io.decode().unwrap();
+         assert_eq!(decoded, hash);
+      }
+   }
+}
@@ -0,0 +1,18 @@
+use hdi::prelude::*;
+
+
+/// Entry publishing the X25519 public key of an Agent, so others can encrypt data for it.
+/// The Agent's key is linked to it with an AgentEncPubKey link.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct EncPubKey {
+   pub value: X25519PubKey,
+}
+
+impl EncPubKey {
+   pub fn new(value: X25519PubKey) -> Self {
+      Self {
+         value,
+      }
+   }
+}
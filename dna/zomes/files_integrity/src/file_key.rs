@@ -0,0 +1,57 @@
+use hdi::prelude::*;
+use zome_delivery_types::*;
+use crate::SEALED_FILE_KEY_TYPE_NAME;
+
+
+/// Name and type of the neutral description of an encrypted public File
+pub const ENCRYPTED_FILE_NAME: &str = "encrypted";
+pub const ENCRYPTED_FILE_TYPE: &str = "application/octet-stream";
+
+
+/// Entry holding the key of an encrypted public File, sent or received.
+/// The public manifest has a neutral description and the data hash of the ciphertext,
+/// so the File's name, size, type and data hash are kept here.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct FileKey {
+   pub manifest_eh: EntryHash,
+   pub value: XSalsa20Poly1305KeyRef,
+   pub data_hash: String,
+   pub name: String,
+   pub size: u64,
+   pub filetype: String,
+}
+
+
+/// Key of an encrypted public File, exported for one recipient, with the File's name, size, type and data hash.
+/// Sent through the delivery zome in the kind of an empty manifest: "<SEALED_FILE_KEY_TYPE_NAME>::<base64>"
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SealedFileKey {
+   pub manifest_eh: EntryHash,
+   pub sealed_key: XSalsa20Poly1305EncryptedData,
+   pub data_hash: String,
+   pub name: String,
+   pub size: u64,
+   pub filetype: String,
+}
+
+impl SealedFileKey {
+   ///
+   pub fn to_kind_info(&self) -> ExternResult<ParcelKind> {
+      use base64::Engine;
+      let io = ExternIO::encode(self.clone())
+         .map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?;
+      let encoded = base64::engine::general_purpose::STANDARD.encode(io.0);
+      Ok(ParcelKind::Manifest(format!("{}::{}", SEALED_FILE_KEY_TYPE_NAME, encoded)))
+   }
+
+   /// Return None if the kind is not a sealed key
+   pub fn from_kind_info(kind_info: &ParcelKind) -> Option<Self> {
+      use base64::Engine;
+      let ParcelKind::Manifest(kind) = kind_info
+         else { return None };
+      let encoded = kind.strip_prefix(&format!("{}::", SEALED_FILE_KEY_TYPE_NAME))?;
+      let bytes = base64::engine::general_purpose::STANDARD.decode(encoded).ok()?;
+      ExternIO(bytes).decode().ok()
+   }
+}
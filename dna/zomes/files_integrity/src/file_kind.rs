@@ -0,0 +1,66 @@
+use zome_delivery_types::*;
+use crate::FILE_TYPE_NAME;
+
+
+pub const INLINE_MARKER: &str = "inline";
+pub const ENCRYPTED_MARKER: &str = "encrypted";
+/// Max size of a serialized inline File description, so it fits in a link tag (1000 bytes)
+pub const MAX_INLINE_DESCRIPTION_SIZE: usize = 1000;
+
+
+/// Kind of a File: "<FILE_TYPE_NAME>::<filetype>".
+/// Tiny Files have their base64 content inlined: "<FILE_TYPE_NAME>::<filetype>::inline::<content>"
+/// Files with encrypted chunks are marked: "<FILE_TYPE_NAME>::<filetype>::encrypted"
+pub fn file_kind_info(filetype: &str, maybe_content: Option<&str>, is_encrypted: bool) -> ParcelKind {
+   if let Some(content) = maybe_content {
+      return ParcelKind::Manifest(format!("{}::{}::{}::{}", FILE_TYPE_NAME, filetype, INLINE_MARKER, content));
+   }
+   if is_encrypted {
+      return ParcelKind::Manifest(format!("{}::{}::{}", FILE_TYPE_NAME, filetype, ENCRYPTED_MARKER));
+   }
+   ParcelKind::Manifest(format!("{}::{}", FILE_TYPE_NAME, filetype))
+}
+
+
+/// Return the base64 content of a File if it is inlined in its description
+pub fn get_inline_content(description: &ParcelDescription) -> Option<String> {
+   let fields = kind_fields(description);
+   if fields.len() != 4 || fields[2] != INLINE_MARKER {
+      return None;
+   }
+   Some(fields[3].to_string())
+}
+
+
+/// True if the chunks of a File are encrypted
+pub fn is_encrypted_file(description: &ParcelDescription) -> bool {
+   let fields = kind_fields(description);
+   fields.len() == 3 && fields[2] == ENCRYPTED_MARKER
+}
+
+
+/// Return the filetype of a File
+pub fn get_filetype(description: &ParcelDescription) -> Option<String> {
+   kind_fields(description).get(1).cloned()
+}
+
+
+/// Return the description of a File with unencrypted chunks
+pub fn without_encryption(description: &ParcelDescription) -> ParcelDescription {
+   if !is_encrypted_file(description) {
+      return description.clone();
+   }
+   let filetype = get_filetype(description).unwrap_or_default();
+   ParcelDescription {
+      kind_info: file_kind_info(&filetype, None, false),
+      ..description.clone()
+   }
+}
+
+
+///
+fn kind_fields(description: &ParcelDescription) -> Vec<String> {
+   let ParcelKind::Manifest(kind) = &description.kind_info
+      else { return Vec::new() };
+   kind.splitn(4, "::").map(|field| field.to_string()).collect()
+}
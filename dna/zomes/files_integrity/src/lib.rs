@@ -8,6 +8,38 @@
 pub mod priv_enc_key;
 //pub use priv_enc_key::*;
 
+mod properties;
+pub use properties::*;
+
+mod attachment;
+pub use attachment::*;
+
+mod data_hash;
+pub use data_hash::*;
+
+mod version;
+pub use version::*;
+
+mod private_folder;
+pub use private_folder::*;
+
+mod public_folder;
+pub use public_folder::*;
+
+mod file_kind;
+pub use file_kind::*;
+
+mod enc_pub_key;
+pub use enc_pub_key::*;
+
+mod file_key;
+pub use file_key::*;
+
+mod replaced_file;
+pub use replaced_file::*;
+
+mod validate;
+
 
 ///--------------------------------------------------------------------------------------------------
 /// Global consts
@@ -18,17 +50,17 @@ pub const FILES_DEFAULT_COORDINATOR_ZOME_NAME: &'static str = "zFiles";
 pub const FILES_DEFAULT_ROLE_NAME: &'static str = "rFiles";
 
 pub const FILE_TYPE_NAME: &'static str = "split_file";
+pub const SEALED_FILE_KEY_TYPE_NAME: &'static str = "sealed_file_key";
 
 
 pub const ATTACHMENTS_ROOT: &str = "public_attachments";
+pub const DATA_HASH_ROOT: &str = "public_data_hashes";
+pub const PUBLIC_FOLDERS_ROOT: &str = "public_folders";
 
 ///-------------------------------------------------------------------------------------------------
 /// Declaration of this zome's entry types
 ///-------------------------------------------------------------------------------------------------
 
-/// Integrity zome is not really necessary but it is defined because the holochain ecosystem
-/// does not support well a zome that does not have one.
-
 use hdi::prelude::*;
 use crate::priv_enc_key::PrivEncKey;
 
@@ -39,6 +71,18 @@ pub enum FilesEntry {
    FileShare(FileShare),
    #[entry_def(required_validations = 1, visibility = "private")]
    PrivEncKey(PrivEncKey),
+   #[entry_def(required_validations = 1, visibility = "private")]
+   PrivateAttachment(PrivateAttachment),
+   #[entry_def(required_validations = 1, visibility = "private")]
+   PrivateFolder(PrivateFolder),
+   #[entry_def(required_validations = 1, visibility = "private")]
+   PrivateFolderItem(PrivateFolderItem),
+   #[entry_def(required_validations = 2, visibility = "public")]
+   EncPubKey(EncPubKey),
+   #[entry_def(required_validations = 1, visibility = "private")]
+   FileKey(FileKey),
+   #[entry_def(required_validations = 1, visibility = "private")]
+   ReplacedFile(ReplacedFile),
 }
 
 
@@ -46,6 +90,14 @@ pub enum FilesEntry {
 #[derive(Serialize, Deserialize)]
 pub enum FilesLinkTypes {
    Attachment,
+   AttachmentPath,
+   AttachedHrl,
+   DataHash,
+   NextVersion,
+   PreviousVersion,
+   PublicFolderPath,
+   PublicFolderFile,
+   AgentEncPubKey,
 }
 
 
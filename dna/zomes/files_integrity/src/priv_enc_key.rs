@@ -1,17 +1,34 @@
 use hdi::prelude::*;
 
 
-/// Entry representing the Private Encryption Key of an Agent
+/// Entry representing a Private Encryption Key of an Agent.
+/// Keys are rotated by creating a new PrivEncKey with a higher id.
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
 pub struct PrivEncKey {
    pub value: XSalsa20Poly1305KeyRef,
+   /// Keys created before rotation existed have id 0
+   #[serde(default)]
+   pub id: u32,
+   /// Imported keys keep the id they had on the source-chain they were exported from
+   #[serde(default)]
+   pub is_imported: bool,
 }
 
 impl PrivEncKey {
-   pub fn new(value: XSalsa20Poly1305KeyRef) -> Self {
+   pub fn new(value: XSalsa20Poly1305KeyRef, id: u32) -> Self {
       Self {
          value,
+         id,
+         is_imported: false,
+      }
+   }
+
+   pub fn new_imported(value: XSalsa20Poly1305KeyRef, id: u32) -> Self {
+      Self {
+         value,
+         id,
+         is_imported: true,
       }
    }
 }
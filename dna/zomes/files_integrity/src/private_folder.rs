@@ -0,0 +1,21 @@
+use hdi::prelude::*;
+
+
+/// Entry representing a folder of private Files.
+/// A folder without parent is at the root.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct PrivateFolder {
+   pub name: String,
+   pub parent: Option<EntryHash>,
+}
+
+
+/// Entry representing the placement of a private File in a PrivateFolder.
+/// A File without PrivateFolderItem is at the root.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct PrivateFolderItem {
+   pub folder_eh: EntryHash,
+   pub manifest_eh: EntryHash,
+}
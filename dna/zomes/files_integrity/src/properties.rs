@@ -0,0 +1,28 @@
+use hdi::prelude::*;
+
+/// Dna properties
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+#[serde(rename_all = "camelCase")]
+pub struct FilesProperties {
+   pub max_chunk_size: u32,
+   pub max_parcel_size: u64,
+   pub min_parcel_name_length: u16,
+   pub max_parcel_name_length: u16,
+   /// Size limit of Files returned whole by get_file_bytes()
+   pub max_file_bytes_size: u64,
+   /// Size limit of Files that can have their content inlined in their manifest
+   pub max_inline_file_size: u64,
+}
+
+
+/// Return the DNA properties
+pub fn get_properties() -> ExternResult<FilesProperties> {
+   let dna_info = dna_info()?;
+   let props = dna_info.modifiers.properties;
+   let maybe_properties: Result<FilesProperties, <FilesProperties as TryFrom<SerializedBytes>>::Error> = props.try_into();
+   if let Err(e) = maybe_properties {
+      debug!("Deserializing FilesZome properties failed: {:?}", e);
+      return Err(wasm_error!("Deserializing FilesZome properties failed: {:?}", e));
+   }
+   Ok(maybe_properties.unwrap())
+}
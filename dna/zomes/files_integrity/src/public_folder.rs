@@ -0,0 +1,59 @@
+use hdi::prelude::*;
+use crate::PUBLIC_FOLDERS_ROOT;
+
+
+/// LinkTag of a PublicFolderFile link.
+/// Holds the folder path so validation can check that the link's base is this folder.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct PublicFolderTag {
+   pub path: String,
+}
+
+impl PublicFolderTag {
+   pub fn new(path: String) -> Self {
+      Self {
+         path,
+      }
+   }
+
+   ///
+   pub fn to_link_tag(&self) -> ExternResult<LinkTag> {
+      let sb = SerializedBytes::try_from(self.clone())
+         .map_err(|e| wasm_error!(e))?;
+      Ok(LinkTag::new(sb.bytes().to_owned()))
+   }
+
+   ///
+   pub fn from_link_tag(tag: &LinkTag) -> ExternResult<Self> {
+      let sb = SerializedBytes::from(UnsafeBytes::from(tag.0.clone()));
+      let folder_tag = Self::try_from(sb)
+         .map_err(|e| wasm_error!(e))?;
+      Ok(folder_tag)
+   }
+}
+
+
+/// Split a folder path like "Design/2026/Q3" into folder names.
+/// An empty path is the root.
+pub fn parse_folder_path(path: &str) -> Result<Vec<String>, String> {
+   let names: Vec<String> = path.split('/')
+      .map(|name| name.trim().to_string())
+      .collect();
+   if names.len() == 1 && names[0].is_empty() {
+      return Ok(Vec::new());
+   }
+   if names.iter().any(|name| name.is_empty()) {
+      return Err(format!("Folder path '{}' has an empty folder name", path));
+   }
+   Ok(names)
+}
+
+
+/// Path of a public folder: "<PUBLIC_FOLDERS_ROOT>.<name>.<name>..."
+pub fn public_folder_path(names: &[String]) -> Path {
+   let mut path = Path::from(format!("{}", PUBLIC_FOLDERS_ROOT));
+   for name in names {
+      path.append_component(name.to_owned().into());
+   }
+   path
+}
@@ -0,0 +1,11 @@
+use hdi::prelude::*;
+
+
+/// Entry recording that one of my private Files has been replaced by a new manifest,
+/// e.g. when it has been renamed or re-encrypted to my newest PrivEncKey.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ReplacedFile {
+   pub previous_eh: EntryHash,
+   pub eh: EntryHash,
+}
@@ -0,0 +1,252 @@
+use hdi::prelude::*;
+use zome_delivery_types::*;
+use crate::*;
+use crate::priv_enc_key::PrivEncKey;
+
+
+/// Zome Callback
+#[hdk_extern]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+   match op.flattened::<FilesEntry, FilesLinkTypes>()? {
+      FlatOp::StoreRecord(OpRecord::CreatePrivateEntry { app_entry_type, action }) => {
+         match app_entry_type {
+            FilesEntryTypes::PrivEncKey => validate_create_enc_key(&op, action),
+            _ => Ok(ValidateCallbackResult::Valid),
+         }
+      },
+      FlatOp::StoreRecord(OpRecord::UpdatePrivateEntry { app_entry_type, .. }) => {
+         match app_entry_type {
+            FilesEntryTypes::PrivEncKey => Ok(ValidateCallbackResult::Invalid("Private encryption key cannot be updated".to_string())),
+            _ => Ok(ValidateCallbackResult::Valid),
+         }
+      },
+      FlatOp::RegisterCreateLink { base_address, target_address, tag, link_type, action } => {
+         match link_type {
+            FilesLinkTypes::Attachment => validate_create_attachment(target_address, base_address, tag),
+            FilesLinkTypes::AttachedHrl => validate_create_attachment(base_address, target_address, tag),
+            FilesLinkTypes::DataHash => validate_create_data_hash(base_address, target_address),
+            FilesLinkTypes::NextVersion => validate_create_version(base_address, target_address, tag, action),
+            FilesLinkTypes::PreviousVersion => validate_create_version(target_address, base_address, tag, action),
+            FilesLinkTypes::PublicFolderFile => validate_create_public_folder_file(base_address, target_address, tag),
+            FilesLinkTypes::AgentEncPubKey => validate_create_agent_enc_pub_key(base_address, target_address, action),
+            /// Path links do not show up in attachment queries.
+            FilesLinkTypes::AttachmentPath => Ok(ValidateCallbackResult::Valid),
+            FilesLinkTypes::PublicFolderPath => Ok(ValidateCallbackResult::Valid),
+         }
+      },
+      FlatOp::RegisterDeleteLink { original_action, link_type, action, .. } => {
+         match link_type {
+            FilesLinkTypes::Attachment |
+            FilesLinkTypes::AttachedHrl => validate_delete_attachment(original_action, action),
+            FilesLinkTypes::AttachmentPath |
+            FilesLinkTypes::PublicFolderPath => Ok(ValidateCallbackResult::Invalid("Path links cannot be deleted".to_string())),
+            FilesLinkTypes::DataHash |
+            FilesLinkTypes::PublicFolderFile |
+            FilesLinkTypes::AgentEncPubKey => validate_delete_by_author(original_action, action),
+            FilesLinkTypes::NextVersion |
+            FilesLinkTypes::PreviousVersion => Ok(ValidateCallbackResult::Invalid("Version links cannot be deleted".to_string())),
+         }
+      },
+      _ => Ok(ValidateCallbackResult::Valid),
+   }
+}
+
+
+/// A new PrivEncKey must have an id strictly greater than the ids of the previous ones on its source-chain,
+/// so the newest key is the one with the highest id. Imported keys keep their id.
+/// Private entries are only available to their author, so this is checked when the author commits the key.
+fn validate_create_enc_key(op: &Op, action: Create) -> ExternResult<ValidateCallbackResult> {
+   let Op::StoreRecord(StoreRecord { record }) = op
+      else { return Ok(ValidateCallbackResult::Valid) };
+   let Some(entry) = record.entry().as_option()
+      else { return Ok(ValidateCallbackResult::Valid) };
+   let Ok(key) = PrivEncKey::try_from(entry)
+      else { return Ok(ValidateCallbackResult::Invalid("PrivEncKey entry is malformed".to_string())) };
+   if key.is_imported {
+      return Ok(ValidateCallbackResult::Valid);
+   }
+   let activity = must_get_agent_activity(action.author.clone(), ChainFilter::new(action.prev_action.clone()))?;
+   for item in activity {
+      let previous_action = item.action.action();
+      if previous_action.entry_type() != Some(&action.entry_type) {
+         continue;
+      }
+      let Some(previous_eh) = previous_action.entry_hash()
+         else { continue };
+      let Ok(previous) = PrivEncKey::try_from(must_get_entry(previous_eh.to_owned())?.content)
+         else { return Ok(ValidateCallbackResult::Invalid("Previous PrivEncKey entry is malformed".to_string())) };
+      if previous.id >= key.id {
+         return Ok(ValidateCallbackResult::Invalid(format!("PrivEncKey id must be greater than {}", previous.id)));
+      }
+   }
+   /// Done
+   Ok(ValidateCallbackResult::Valid)
+}
+
+
+/// An Attachment link must be between a HRL path and a public File manifest.
+/// Same rules apply to both directions (Attachment & AttachedHrl).
+fn validate_create_attachment(manifest_address: AnyLinkableHash, hrl_address: AnyLinkableHash, tag: LinkTag) -> ExternResult<ValidateCallbackResult> {
+   /// HRL side must be the HRL path stored in the tag
+   let Ok(attachment_tag) = AttachmentTag::from_link_tag(&tag)
+      else { return Ok(ValidateCallbackResult::Invalid("Attachment link tag is malformed".to_string())) };
+   let hrl_eh = hrl_to_path(&attachment_tag.hrl).path_entry_hash()?;
+   if hrl_address.into_entry_hash() != Some(hrl_eh) {
+      return Ok(ValidateCallbackResult::Invalid("Attachment link does not point to the HRL path".to_string()));
+   }
+   /// Manifest side must be the manifest committed at the tag's ActionHash
+   let Some(manifest_eh) = manifest_address.into_entry_hash()
+      else { return Ok(ValidateCallbackResult::Invalid("Attachment link manifest is not an entry".to_string())) };
+   let record = must_get_valid_record(attachment_tag.manifest_ah)?;
+   if record.action().entry_hash() != Some(&manifest_eh) {
+      return Ok(ValidateCallbackResult::Invalid("Attachment link tag does not match its manifest".to_string()));
+   }
+   /// Must be a File manifest
+   let Ok(Some(manifest)) = record.entry().to_app_option::<ParcelManifest>()
+      else { return Ok(ValidateCallbackResult::Invalid("Attachment link manifest is not a ParcelManifest".to_string())) };
+   if let Err(reason) = check_parcel_is_file(&manifest.description) {
+      return Ok(ValidateCallbackResult::Invalid(reason));
+   }
+   if manifest.description.visibility != EntryVisibility::Public {
+      return Ok(ValidateCallbackResult::Invalid("Attachment link manifest is not public".to_string()));
+   }
+   /// Done
+   Ok(ValidateCallbackResult::Valid)
+}
+
+
+/// Only the link's author or the manifest's author can delete an Attachment link
+fn validate_delete_attachment(original_action: CreateLink, action: DeleteLink) -> ExternResult<ValidateCallbackResult> {
+   if action.author == original_action.author {
+      return Ok(ValidateCallbackResult::Valid);
+   }
+   let Ok(attachment_tag) = AttachmentTag::from_link_tag(&original_action.tag)
+      else { return Ok(ValidateCallbackResult::Invalid("Attachment link tag is malformed".to_string())) };
+   let record = must_get_valid_record(attachment_tag.manifest_ah)?;
+   if &action.author != record.action().author() {
+      return Ok(ValidateCallbackResult::Invalid("Only the attacher or the File's author can delete an Attachment".to_string()));
+   }
+   /// Done
+   Ok(ValidateCallbackResult::Valid)
+}
+
+
+/// A DataHash link must go from the data hash path to a public File manifest with that data hash
+fn validate_create_data_hash(base_address: AnyLinkableHash, target_address: AnyLinkableHash) -> ExternResult<ValidateCallbackResult> {
+   let Some(manifest_eh) = target_address.into_entry_hash()
+      else { return Ok(ValidateCallbackResult::Invalid("DataHash link target is not an entry".to_string())) };
+   let entry = must_get_entry(manifest_eh)?.content;
+   let Ok(manifest) = ParcelManifest::try_from(entry)
+      else { return Ok(ValidateCallbackResult::Invalid("DataHash link target is not a ParcelManifest".to_string())) };
+   if let Err(reason) = check_parcel_is_file(&manifest.description) {
+      return Ok(ValidateCallbackResult::Invalid(reason));
+   }
+   if manifest.description.visibility != EntryVisibility::Public {
+      return Ok(ValidateCallbackResult::Invalid("DataHash link target is not a public manifest".to_string()));
+   }
+   if is_encrypted_file(&manifest.description) {
+      return Ok(ValidateCallbackResult::Invalid("DataHash link target is an encrypted File".to_string()));
+   }
+   let path_eh = data_hash_path(&manifest.data_hash).path_entry_hash()?;
+   if base_address.into_entry_hash() != Some(path_eh) {
+      return Ok(ValidateCallbackResult::Invalid("DataHash link base is not the manifest's data hash path".to_string()));
+   }
+   /// Done
+   Ok(ValidateCallbackResult::Valid)
+}
+
+
+/// Only the link's author can delete it
+fn validate_delete_by_author(original_action: CreateLink, action: DeleteLink) -> ExternResult<ValidateCallbackResult> {
+   if action.author != original_action.author {
+      return Ok(ValidateCallbackResult::Invalid("Only the author of a link can delete it".to_string()));
+   }
+   Ok(ValidateCallbackResult::Valid)
+}
+
+
+/// A version link must be between two public File manifests.
+/// Only the author of the previous version can link a new version to it.
+/// Same rules apply to both directions (NextVersion & PreviousVersion).
+fn validate_create_version(previous_address: AnyLinkableHash, next_address: AnyLinkableHash, tag: LinkTag, action: CreateLink) -> ExternResult<ValidateCallbackResult> {
+   let Ok(version_tag) = VersionTag::from_link_tag(&tag)
+      else { return Ok(ValidateCallbackResult::Invalid("Version link tag is malformed".to_string())) };
+   /// Previous must be the manifest committed at the tag's ActionHash, by the link's author
+   let Some(previous_eh) = previous_address.into_entry_hash()
+      else { return Ok(ValidateCallbackResult::Invalid("Previous version is not an entry".to_string())) };
+   let record = must_get_valid_record(version_tag.previous_ah)?;
+   if record.action().entry_hash() != Some(&previous_eh) {
+      return Ok(ValidateCallbackResult::Invalid("Version link tag does not match previous version".to_string()));
+   }
+   if record.action().author() != &action.author {
+      return Ok(ValidateCallbackResult::Invalid("Only the author of a File can make a new version of it".to_string()));
+   }
+   let Ok(Some(previous)) = record.entry().to_app_option::<ParcelManifest>()
+      else { return Ok(ValidateCallbackResult::Invalid("Previous version is not a ParcelManifest".to_string())) };
+   /// Next must be a File manifest
+   let Some(next_eh) = next_address.into_entry_hash()
+      else { return Ok(ValidateCallbackResult::Invalid("Next version is not an entry".to_string())) };
+   if next_eh == previous_eh {
+      return Ok(ValidateCallbackResult::Invalid("A File cannot be a new version of itself".to_string()));
+   }
+   let entry = must_get_entry(next_eh)?.content;
+   let Ok(next) = ParcelManifest::try_from(entry)
+      else { return Ok(ValidateCallbackResult::Invalid("Next version is not a ParcelManifest".to_string())) };
+   /// Both must be public Files
+   for manifest in [previous, next] {
+      if let Err(reason) = check_parcel_is_file(&manifest.description) {
+         return Ok(ValidateCallbackResult::Invalid(reason));
+      }
+      if manifest.description.visibility != EntryVisibility::Public {
+         return Ok(ValidateCallbackResult::Invalid("Versions must be public manifests".to_string()));
+      }
+   }
+   /// Done
+   Ok(ValidateCallbackResult::Valid)
+}
+
+
+/// A PublicFolderFile link must go from a public folder path to a public File manifest
+fn validate_create_public_folder_file(base_address: AnyLinkableHash, target_address: AnyLinkableHash, tag: LinkTag) -> ExternResult<ValidateCallbackResult> {
+   /// Base must be the folder path stored in the tag
+   let Ok(folder_tag) = PublicFolderTag::from_link_tag(&tag)
+      else { return Ok(ValidateCallbackResult::Invalid("PublicFolderFile link tag is malformed".to_string())) };
+   let names = match parse_folder_path(&folder_tag.path) {
+      Ok(names) => names,
+      Err(reason) => return Ok(ValidateCallbackResult::Invalid(reason)),
+   };
+   let folder_eh = public_folder_path(&names).path_entry_hash()?;
+   if base_address.into_entry_hash() != Some(folder_eh) {
+      return Ok(ValidateCallbackResult::Invalid("PublicFolderFile link base is not the folder path".to_string()));
+   }
+   /// Target must be a public File manifest
+   let Some(manifest_eh) = target_address.into_entry_hash()
+      else { return Ok(ValidateCallbackResult::Invalid("PublicFolderFile link target is not an entry".to_string())) };
+   let entry = must_get_entry(manifest_eh)?.content;
+   let Ok(manifest) = ParcelManifest::try_from(entry)
+      else { return Ok(ValidateCallbackResult::Invalid("PublicFolderFile link target is not a ParcelManifest".to_string())) };
+   if let Err(reason) = check_parcel_is_file(&manifest.description) {
+      return Ok(ValidateCallbackResult::Invalid(reason));
+   }
+   if manifest.description.visibility != EntryVisibility::Public {
+      return Ok(ValidateCallbackResult::Invalid("PublicFolderFile link target is not a public manifest".to_string()));
+   }
+   /// Done
+   Ok(ValidateCallbackResult::Valid)
+}
+
+
+/// An AgentEncPubKey link must go from its author's AgentPubKey to an EncPubKey entry
+fn validate_create_agent_enc_pub_key(base_address: AnyLinkableHash, target_address: AnyLinkableHash, action: CreateLink) -> ExternResult<ValidateCallbackResult> {
+   if base_address.into_agent_pub_key() != Some(action.author) {
+      return Ok(ValidateCallbackResult::Invalid("AgentEncPubKey link base is not its author".to_string()));
+   }
+   let Some(key_eh) = target_address.into_entry_hash()
+      else { return Ok(ValidateCallbackResult::Invalid("AgentEncPubKey link target is not an entry".to_string())) };
+   let entry = must_get_entry(key_eh)?.content;
+   if EncPubKey::try_from(entry).is_err() {
+      return Ok(ValidateCallbackResult::Invalid("AgentEncPubKey link target is not an EncPubKey".to_string()));
+   }
+   /// Done
+   Ok(ValidateCallbackResult::Valid)
+}
@@ -0,0 +1,32 @@
+use hdi::prelude::*;
+
+
+/// LinkTag of NextVersion & PreviousVersion links.
+/// Holds the previous manifest's ActionHash so validation can check who is making a new version.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub struct VersionTag {
+   pub previous_ah: ActionHash,
+}
+
+impl VersionTag {
+   pub fn new(previous_ah: ActionHash) -> Self {
+      Self {
+         previous_ah,
+      }
+   }
+
+   ///
+   pub fn to_link_tag(&self) -> ExternResult<LinkTag> {
+      let sb = SerializedBytes::try_from(self.clone())
+         .map_err(|e| wasm_error!(e))?;
+      Ok(LinkTag::new(sb.bytes().to_owned()))
+   }
+
+   ///
+   pub fn from_link_tag(tag: &LinkTag) -> ExternResult<Self> {
+      let sb = SerializedBytes::from(UnsafeBytes::from(tag.0.clone()));
+      let version_tag = Self::try_from(sb)
+         .map_err(|e| wasm_error!(e))?;
+      Ok(version_tag)
+   }
+}
@@ -1,7 +1,7 @@
 use hdk::prelude::*;
 use zome_utils::*;
 use zome_tagging_integrity::*;
-use crate::TaggingInput;
+use crate::{TaggingInput, UntagInput};
 
 
 fn root_path() -> ExternResult<TypedPath> {
@@ -107,14 +107,44 @@ fn tag_public_entry(input: TaggingInput) -> ExternResult<()> {
 }
 
 
+/// Remove a tag I have set on a public entry
+#[hdk_extern]
+fn untag_public_entry(input: UntagInput) -> ExternResult<()> {
+    std::panic::set_hook(Box::new(zome_panic_hook));
+    let me = agent_info()?.agent_latest_pubkey;
+    /// Get Entry -> Tag links
+    let mut links: Vec<Link> = get_links(input.target.clone(), TaggingLinkTypes::PublicTags, None)?
+        .into_iter()
+        .filter(|link| link.author == me && tag2str(&link.tag).ok() == Some(input.tag.clone()))
+        .collect();
+    if links.is_empty() {
+        return error("Tag not found on public entry");
+    }
+    /// Get reverse links
+    let mut tp = root_path()?;
+    tp.path.append_component(input.tag.into());
+    let reverse_links: Vec<Link> = get_links(tp.path_entry_hash()?, TaggingLinkTypes::PublicEntry, None)?
+        .into_iter()
+        .filter(|link| link.author == me && link.target.clone().into_entry_hash() == Some(input.target.clone()))
+        .collect();
+    links.extend(reverse_links);
+    /// Delete links
+    for link in links {
+        let _ = delete_link(link.create_link_hash)?;
+    }
+    /// Done
+    Ok(())
+}
+
+
 ///
 #[hdk_extern]
 pub fn get_public_tags(eh: EntryHash) -> ExternResult<Vec<String>> {
     std::panic::set_hook(Box::new(zome_panic_hook));
-    /// Make sure entry exist and is private
+    /// Make sure entry exist and is public
     let _ = get_public_entry(eh.clone())?;
-    /// Grab private tags
-    let links = get_links(eh, TaggingLinkTypes::PrivateTags, None)?;
+    /// Grab public tags
+    let links = get_links(eh, TaggingLinkTypes::PublicTags, None)?;
     let res = links.into_iter()
         .map(|link| (tag2str(&link.tag).unwrap()))
         .collect();
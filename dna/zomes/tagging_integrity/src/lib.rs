@@ -5,6 +5,7 @@
 #![allow(unused_attributes)]
 
 mod properties;
+mod validate;
 
 pub use properties::*;
 
@@ -0,0 +1,21 @@
+use hdi::prelude::*;
+use crate::*;
+
+
+/// Zome Callback
+#[hdk_extern]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+   match op.flattened::<TaggingEntry, TaggingLinkTypes>()? {
+      FlatOp::RegisterDeleteLink { original_action, action, .. } => validate_delete_tag_link(original_action, action),
+      _ => Ok(ValidateCallbackResult::Valid),
+   }
+}
+
+
+/// Only the author of a tag link can delete it
+fn validate_delete_tag_link(original_action: CreateLink, action: DeleteLink) -> ExternResult<ValidateCallbackResult> {
+   if action.author != original_action.author {
+      return Ok(ValidateCallbackResult::Invalid("Only the author of a tag link can delete it".to_string()));
+   }
+   Ok(ValidateCallbackResult::Valid)
+}
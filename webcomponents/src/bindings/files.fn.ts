@@ -9,25 +9,64 @@ export const filesFunctionNames: FunctionName[] = [
 	"get_zome_info", 
 	"get_dna_info",
 	"attach_to_hrl",
+	"attach_to_hrl_privately",
+	"detach_from_hrl",
 	"get_files_from_hrl",
-
-
+	"get_files_from_hrls",
+	"count_files_for_hrls",
+	"get_hrls_for_file",
 	"commit_private_file",
+	"delete_private_file",
+	"export_enc_key",
+	"import_enc_key",
+	"rotate_enc_key",
+	"get_current_private_file",
 	"encrypt_data",
+	"encrypt_data_with_key_id",
 	"decrypt_data",
+	"get_encrypted_chunk_size",
+	"send_encrypted_file",
+	"accept_encrypted_file",
+	"receive_encrypted_files",
+	"get_unreplied_sealed_key_notices",
+	"find_file_by_data_hash",
+	"get_any_record",
 	"get_ah",
 	"get_file_info",
 	"get_private_files",
 	"get_local_public_files",
 	"get_private_files_from",
 	"get_unreplied_notices",
+	"ensure_enc_pub_key",
+	"get_enc_pub_keys",
+	"encrypt_for",
+	"decrypt_from",
+	"create_folder",
+	"move_file_to_folder",
+	"list_folder",
+	"get_folder_tree",
 	"probe_public_files",
 	"process_inbox",
+	"create_public_folder",
+	"add_public_file_to_folder",
+	"browse_public_folder",
 	"publish_file_manifest",
+	"publish_new_version",
+	"get_file_versions",
+	"get_latest_version",
+	"publish_private_file",
+	"read_file_chunk",
+	"read_file_range",
+	"get_file_bytes",
+	"rename_file",
 	"refuse_file_share",
 	"accept_file_share",
+	"save_public_file",
 	"send_file",
+	"unpublish_file",
+	"verify_file",
 	"write_private_file_chunks",
+	"write_encrypted_private_file_chunks",
 	"write_public_file_chunks",];
 
 
@@ -1,6 +1,6 @@
 /* This file is generated by zits. Do not edit manually */
 
-import {ATTACHMENTS_ROOT, FILES_DEFAULT_COORDINATOR_ZOME_NAME, FILES_DEFAULT_INTEGRITY_ZOME_NAME, FILES_DEFAULT_ROLE_NAME, FILE_TYPE_NAME, FilesEntry, FilesLinkTypes, AttachInput, FileShare, PrivEncKey, SendFileInput, WriteManifestInput, } from './files.types';
+import {ATTACHMENTS_ROOT, DATA_HASH_ROOT, ENCRYPTED_CHUNK_PREFIX, ENCRYPTED_FILE_NAME, ENCRYPTED_FILE_TYPE, ENCRYPTED_MARKER, FILES_DEFAULT_COORDINATOR_ZOME_NAME, FILES_DEFAULT_INTEGRITY_ZOME_NAME, FILES_DEFAULT_ROLE_NAME, FILE_TYPE_NAME, INLINE_MARKER, MAX_INLINE_DESCRIPTION_SIZE, PUBLIC_FOLDERS_ROOT, SEALED_FILE_KEY_TYPE_NAME, FilesEntry, FilesLinkTypes, AddPublicFileInput, AttachInput, AttachmentInfo, AttachmentTag, ContentHash, CreateFolderInput, DecryptFromInput, EncPubKey, EncryptForInput, EncryptedDataInput, FileKey, FileShare, FileVerification, FilesProperties, FilesSignal, FolderContent, FolderTree, ImportEncKeyInput, KeyedEncryptedData, MoveFileInput, NewVersionInput, PrivEncKey, PrivateAttachment, PrivateFolder, PrivateFolderItem, PublicFolderContent, PublicFolderTag, PublishPrivateFileInput, ReadChunkInput, ReadRangeInput, RenameFileInput, ReplacedFile, SealedFileKey, SealedFileNotice, SendEncryptedFileInput, SendFileInput, VersionTag, WriteManifestInput, } from './files.types';
 import {
 /** types.ts */
 HoloHash,
@@ -162,22 +162,92 @@ export class FilesProxy extends ZomeProxy {
     return this.call('attach_to_hrl', input);
   }
 
-  async getFilesFromHrl(hrl: [DnaHash, EntryHash]): Promise<EntryHash[]> {
+  async attachToHrlPrivately(input: AttachInput): Promise<ActionHash> {
+    return this.call('attach_to_hrl_privately', input);
+  }
+
+  async detachFromHrl(input: AttachInput): Promise<void> {
+    return this.call('detach_from_hrl', input);
+  }
+
+  async getFilesFromHrl(hrl: [DnaHash, EntryHash]): Promise<AttachmentInfo[]> {
     return this.call('get_files_from_hrl', hrl);
   }
 
+  async getFilesFromHrls(hrls: [DnaHash, EntryHash][]): Promise<[[DnaHash, EntryHash], AttachmentInfo[]][]> {
+    return this.call('get_files_from_hrls', hrls);
+  }
+
+  async countFilesForHrls(hrls: [DnaHash, EntryHash][]): Promise<[[DnaHash, EntryHash], number][]> {
+    return this.call('count_files_for_hrls', hrls);
+  }
 
+  async getHrlsForFile(manifestEh: EntryHash): Promise<[DnaHash, EntryHash][]> {
+    return this.call('get_hrls_for_file', manifestEh);
+  }
 
-  async commitPrivateFile(input: WriteManifestInput): Promise<[EntryHash, ParcelDescription]> {
+  async commitPrivateFile(input: WriteManifestInput): Promise<[EntryHash, ParcelDescription, boolean, EntryHash | null]> {
     return this.call('commit_private_file', input);
   }
 
+  async deletePrivateFile(manifestEh: EntryHash): Promise<void> {
+    return this.call('delete_private_file', manifestEh);
+  }
+
+  async exportEncKey(passphrase: string): Promise<string> {
+    return this.call('export_enc_key', passphrase);
+  }
+
+  async importEncKey(input: ImportEncKeyInput): Promise<number[]> {
+    return this.call('import_enc_key', input);
+  }
+
+  async rotateEncKey(reencrypt: boolean): Promise<number> {
+    return this.call('rotate_enc_key', reencrypt);
+  }
+
+  async getCurrentPrivateFile(manifestEh: EntryHash): Promise<EntryHash> {
+    return this.call('get_current_private_file', manifestEh);
+  }
+
   async encryptData(data: Uint8Array): Promise<unknown> {
     return this.call('encrypt_data', data);
   }
 
-  async decryptData(data: unknown): Promise<Uint8Array> {
-    return this.call('decrypt_data', data);
+  async encryptDataWithKeyId(data: Uint8Array): Promise<KeyedEncryptedData> {
+    return this.call('encrypt_data_with_key_id', data);
+  }
+
+  async decryptData(input: EncryptedDataInput): Promise<Uint8Array> {
+    return this.call('decrypt_data', input);
+  }
+
+  async getEncryptedChunkSize(): Promise<number> {
+    return this.call('get_encrypted_chunk_size', null);
+  }
+
+  async sendEncryptedFile(input: SendEncryptedFileInput): Promise<[EntryHash, ActionHash[]]> {
+    return this.call('send_encrypted_file', input);
+  }
+
+  async acceptEncryptedFile(parcelEh: EntryHash): Promise<EntryHash> {
+    return this.call('accept_encrypted_file', parcelEh);
+  }
+
+  async receiveEncryptedFiles(): Promise<EntryHash[]> {
+    return this.call('receive_encrypted_files', null);
+  }
+
+  async getUnrepliedSealedKeyNotices(): Promise<SealedFileNotice[]> {
+    return this.call('get_unreplied_sealed_key_notices', null);
+  }
+
+  async findFileByDataHash(dataHash: ContentHash): Promise<[EntryHash, ParcelManifest, boolean] | null> {
+    return this.call('find_file_by_data_hash', dataHash);
+  }
+
+  async getAnyRecord(hash: AnyDhtHash): Promise<HcRecord | null> {
+    return this.call('get_any_record', hash);
   }
 
   async getAh(eh: EntryHash): Promise<ActionHash | null> {
@@ -204,6 +274,38 @@ export class FilesProxy extends ZomeProxy {
     return this.call('get_unreplied_notices', null);
   }
 
+  async ensureEncPubKey(): Promise<unknown> {
+    return this.call('ensure_enc_pub_key', null);
+  }
+
+  async getEncPubKeys(agent: AgentPubKey): Promise<unknown[]> {
+    return this.call('get_enc_pub_keys', agent);
+  }
+
+  async encryptFor(input: EncryptForInput): Promise<[AgentPubKey, unknown][]> {
+    return this.call('encrypt_for', input);
+  }
+
+  async decryptFrom(input: DecryptFromInput): Promise<Uint8Array> {
+    return this.call('decrypt_from', input);
+  }
+
+  async createFolder(input: CreateFolderInput): Promise<EntryHash> {
+    return this.call('create_folder', input);
+  }
+
+  async moveFileToFolder(input: MoveFileInput): Promise<void> {
+    return this.call('move_file_to_folder', input);
+  }
+
+  async listFolder(folderEh: EntryHash | null): Promise<FolderContent> {
+    return this.call('list_folder', folderEh);
+  }
+
+  async getFolderTree(folderEh: EntryHash | null): Promise<FolderTree> {
+    return this.call('get_folder_tree', folderEh);
+  }
+
   async probePublicFiles(): Promise<[ParcelReference, Timestamp, AgentPubKey][]> {
     return this.call('probe_public_files', null);
   }
@@ -212,10 +314,54 @@ export class FilesProxy extends ZomeProxy {
     return this.call('process_inbox', null);
   }
 
-  async publishFileManifest(input: WriteManifestInput): Promise<[EntryHash, ParcelDescription]> {
+  async createPublicFolder(path: string): Promise<EntryHash> {
+    return this.call('create_public_folder', path);
+  }
+
+  async addPublicFileToFolder(input: AddPublicFileInput): Promise<ActionHash> {
+    return this.call('add_public_file_to_folder', input);
+  }
+
+  async browsePublicFolder(path: string): Promise<PublicFolderContent> {
+    return this.call('browse_public_folder', path);
+  }
+
+  async publishFileManifest(input: WriteManifestInput): Promise<[EntryHash, ParcelDescription, boolean, EntryHash | null]> {
     return this.call('publish_file_manifest', input);
   }
 
+  async publishNewVersion(input: NewVersionInput): Promise<[EntryHash, ParcelDescription]> {
+    return this.call('publish_new_version', input);
+  }
+
+  async getFileVersions(eh: EntryHash): Promise<[EntryHash, ParcelDescription][]> {
+    return this.call('get_file_versions', eh);
+  }
+
+  async getLatestVersion(eh: EntryHash): Promise<EntryHash> {
+    return this.call('get_latest_version', eh);
+  }
+
+  async publishPrivateFile(input: PublishPrivateFileInput): Promise<[EntryHash, ParcelDescription, boolean]> {
+    return this.call('publish_private_file', input);
+  }
+
+  async readFileChunk(input: ReadChunkInput): Promise<string> {
+    return this.call('read_file_chunk', input);
+  }
+
+  async readFileRange(input: ReadRangeInput): Promise<Uint8Array> {
+    return this.call('read_file_range', input);
+  }
+
+  async getFileBytes(manifestEh: EntryHash): Promise<Uint8Array> {
+    return this.call('get_file_bytes', manifestEh);
+  }
+
+  async renameFile(input: RenameFileInput): Promise<[EntryHash, ParcelDescription]> {
+    return this.call('rename_file', input);
+  }
+
   async refuseFileShare(parcelEh: EntryHash): Promise<EntryHash> {
     return this.call('refuse_file_share', parcelEh);
   }
@@ -224,14 +370,30 @@ export class FilesProxy extends ZomeProxy {
     return this.call('accept_file_share', parcelEh);
   }
 
+  async savePublicFile(manifestEh: EntryHash): Promise<[EntryHash, ParcelDescription, boolean]> {
+    return this.call('save_public_file', manifestEh);
+  }
+
   async sendFile(input: SendFileInput): Promise<ActionHash> {
     return this.call('send_file', input);
   }
 
+  async unpublishFile(manifestEh: EntryHash): Promise<void> {
+    return this.call('unpublish_file', manifestEh);
+  }
+
+  async verifyFile(manifestEh: EntryHash): Promise<FileVerification> {
+    return this.call('verify_file', manifestEh);
+  }
+
   async writePrivateFileChunks(chunks: ParcelChunk[]): Promise<EntryHash[]> {
     return this.call('write_private_file_chunks', chunks);
   }
 
+  async writeEncryptedPrivateFileChunks(chunks: ParcelChunk[]): Promise<EntryHash[]> {
+    return this.call('write_encrypted_private_file_chunks', chunks);
+  }
+
   async writePublicFileChunks(chunks: ParcelChunk[]): Promise<EntryHash[]> {
     return this.call('write_public_file_chunks', chunks);
   }
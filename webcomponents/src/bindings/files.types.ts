@@ -150,14 +150,148 @@ import {DistributionStrategy, ParcelManifest, ParcelChunk, ParcelDescription, Pa
 export interface AttachInput {
   hrl: [DnaHash, EntryHash]
   manifestEh: EntryHash
+  label?: string
+}
+
+/** A File attached to a HRL */
+export interface AttachmentInfo {
+  manifest_eh: EntryHash
+  author: AgentPubKey
+  timestamp: Timestamp
+  label?: string
+  description: ParcelDescription
+  is_private: boolean
 }
 
 export interface WriteManifestInput {
   filename: string
   filetype: string
-  data_hash: string
+  data_hash: ContentHash
   orig_filesize: number
   chunks: EntryHash[]
+  /** Base64 content of a tiny File, to store it in its manifest instead of in chunks */
+  content?: string
+  /** Chunks have been written with write_encrypted_private_file_chunks() */
+  is_encrypted?: boolean
+}
+
+/**  */
+export interface ImportEncKeyInput {
+  blob: string
+  passphrase: string
+}
+
+/**
+ * Prefix of the data of an encrypted chunk.
+ * Base64 data never contains ':' so plaintext chunks cannot be mistaken for encrypted ones.
+ */
+export const ENCRYPTED_CHUNK_PREFIX = "encrypted::";
+
+/** Data encrypted with one of my PrivEncKeys, tagged with the key's id */
+export interface KeyedEncryptedData {
+  key_id: number
+  data: unknown
+}
+
+/** Data encrypted before key rotation existed is not tagged with a key id */
+export type EncryptedDataInput =
+ | KeyedEncryptedData | unknown;
+
+/**  */
+export interface SendEncryptedFileInput {
+  manifest_eh: EntryHash
+  strategy: DistributionStrategy
+  recipients: AgentPubKey[]
+}
+
+/**  */
+export interface SealedFileNotice {
+  sender: AgentPubKey
+  notice_eh: EntryHash
+  parcel_eh: EntryHash
+  /** Description of the File whose key is sealed, encrypted public Files only have a neutral one */
+  description: ParcelDescription
+}
+
+/**  */
+export interface EncryptForInput {
+  recipients: AgentPubKey[]
+  data: Uint8Array
+}
+
+/**  */
+export interface DecryptFromInput {
+  sender: AgentPubKey
+  data: unknown
+}
+
+/**  */
+export interface CreateFolderInput {
+  name: string
+  parent?: EntryHash
+}
+
+/** No folder means the root. */
+export interface MoveFileInput {
+  manifest_eh: EntryHash
+  folder_eh?: EntryHash
+}
+
+/**  */
+export interface FolderContent {
+  folders: [EntryHash, PrivateFolder][]
+  files: [EntryHash, ParcelManifest][]
+}
+
+/** The root has no folder_eh and an empty name. */
+export interface FolderTree {
+  folder_eh?: EntryHash
+  name: string
+  files: EntryHash[]
+  children: FolderTree[]
+}
+
+/**  */
+export interface AddPublicFileInput {
+  path: string
+  manifest_eh: EntryHash
+}
+
+/**  */
+export interface PublicFolderContent {
+  folders: string[]
+  files: [EntryHash, ParcelDescription, AgentPubKey][]
+}
+
+/**  */
+export interface NewVersionInput {
+  previous_manifest_eh: EntryHash
+  input: WriteManifestInput
+}
+
+/**  */
+export interface PublishPrivateFileInput {
+  manifest_eh: EntryHash
+  keep_private_copy: boolean
+}
+
+/**  */
+export interface ReadRangeInput {
+  manifest_eh: EntryHash
+  offset: number
+  length: number
+}
+
+/**  */
+export interface ReadChunkInput {
+  manifest_eh: EntryHash
+  index: number
+}
+
+/**  */
+export interface RenameFileInput {
+  manifest_eh: EntryHash
+  new_name: string
 }
 
 /**  */
@@ -167,6 +301,25 @@ export interface SendFileInput {
   recipients: AgentPubKey[]
 }
 
+/** Signals emitted by this zome */
+export enum FilesSignalType {
+	SaveProgress = 'SaveProgress',
+	FileReplaced = 'FileReplaced',
+}
+export type FilesSignalVariantSaveProgress = {SaveProgress: [EntryHash, number, number]}
+export type FilesSignalVariantFileReplaced = {FileReplaced: [EntryHash, EntryHash]}
+export type FilesSignal = 
+ | FilesSignalVariantSaveProgress | FilesSignalVariantFileReplaced;
+
+/**  */
+export interface FileVerification {
+  is_valid: boolean
+  /** None if some chunks are missing or cannot be decrypted */
+  computed_data_hash?: ContentHash
+  missing_chunks: number[]
+  corrupt_chunks: number[]
+}
+
 /**
  * --------------------------------------------------------------------------------------------------
  * Global consts
@@ -180,21 +333,47 @@ export const FILES_DEFAULT_ROLE_NAME = "rFiles";
 
 export const FILE_TYPE_NAME = "split_file";
 
+export const SEALED_FILE_KEY_TYPE_NAME = "sealed_file_key";
+
 export const ATTACHMENTS_ROOT = "public_attachments";
 
+export const DATA_HASH_ROOT = "public_data_hashes";
+
+export const PUBLIC_FOLDERS_ROOT = "public_folders";
+
 export enum FilesEntryType {
 	FileShare = 'FileShare',
 	PrivEncKey = 'PrivEncKey',
+	PrivateAttachment = 'PrivateAttachment',
+	PrivateFolder = 'PrivateFolder',
+	PrivateFolderItem = 'PrivateFolderItem',
+	EncPubKey = 'EncPubKey',
+	FileKey = 'FileKey',
+	ReplacedFile = 'ReplacedFile',
 }
 export type FilesEntryVariantFileShare = {FileShare: FileShare}
 export type FilesEntryVariantPrivEncKey = {PrivEncKey: PrivEncKey}
+export type FilesEntryVariantPrivateAttachment = {PrivateAttachment: PrivateAttachment}
+export type FilesEntryVariantPrivateFolder = {PrivateFolder: PrivateFolder}
+export type FilesEntryVariantPrivateFolderItem = {PrivateFolderItem: PrivateFolderItem}
+export type FilesEntryVariantEncPubKey = {EncPubKey: EncPubKey}
+export type FilesEntryVariantFileKey = {FileKey: FileKey}
+export type FilesEntryVariantReplacedFile = {ReplacedFile: ReplacedFile}
 export type FilesEntry = 
- | FilesEntryVariantFileShare | FilesEntryVariantPrivEncKey;
+ | FilesEntryVariantFileShare | FilesEntryVariantPrivEncKey | FilesEntryVariantPrivateAttachment | FilesEntryVariantPrivateFolder | FilesEntryVariantPrivateFolderItem | FilesEntryVariantEncPubKey | FilesEntryVariantFileKey | FilesEntryVariantReplacedFile;
 
 export type FilesLinkTypes =
-  | {Attachment: null};
+  | {Attachment: null} | {AttachmentPath: null} | {AttachedHrl: null} | {DataHash: null} | {NextVersion: null} | {PreviousVersion: null} | {PublicFolderPath: null} | {PublicFolderFile: null} | {AgentEncPubKey: null};
 export enum FilesLinkTypesType {
 	Attachment = 'Attachment',
+	AttachmentPath = 'AttachmentPath',
+	AttachedHrl = 'AttachedHrl',
+	DataHash = 'DataHash',
+	NextVersion = 'NextVersion',
+	PreviousVersion = 'PreviousVersion',
+	PublicFolderPath = 'PublicFolderPath',
+	PublicFolderFile = 'PublicFolderFile',
+	AgentEncPubKey = 'AgentEncPubKey',
 }
 
 /** Bogus Entry */
@@ -202,7 +381,142 @@ export interface FileShare {
   value: string
 }
 
-/** Entry representing the Private Encryption Key of an Agent */
+/**
+ * LinkTag of an Attachment link.
+ * Holds the HRL so validation can check that the link's base is its HRL path,
+ * and the manifest's ActionHash so validation can find the manifest's author.
+ * An optional label gives context about the attachment.
+ */
+export interface AttachmentTag {
+  hrl: [DnaHash, EntryHash]
+  manifest_ah: ActionHash
+  label?: string
+}
+
+/** Entry representing a File attached to a HRL, known only to its author. */
+export interface PrivateAttachment {
+  hrl: [DnaHash, EntryHash]
+  manifest_eh: EntryHash
+  label?: string
+}
+
+/**
+ * Hash of a File's content (the whole base64 string), with its algorithm.
+ * Serialized as a string: "u" + base64url of the multihash (code, length, digest).
+ * SHA-256 keeps the legacy encoding (digest only) so existing manifests and paths stay valid.
+ */
+export type ContentHash = string;
+
+/**
+ * Entry publishing the X25519 public key of an Agent, so others can encrypt data for it.
+ * The Agent's key is linked to it with an AgentEncPubKey link.
+ */
+export interface EncPubKey {
+  value: unknown
+}
+
+/** Name and type of the neutral description of an encrypted public File */
+export const ENCRYPTED_FILE_NAME = "encrypted";
+
+export const ENCRYPTED_FILE_TYPE = "application/octet-stream";
+
+/**
+ * Entry holding the key of an encrypted public File, sent or received.
+ * The public manifest has a neutral description and the data hash of the ciphertext,
+ * so the File's name, size, type and data hash are kept here.
+ */
+export interface FileKey {
+  manifest_eh: EntryHash
+  value: unknown
+  data_hash: string
+  name: string
+  size: number
+  filetype: string
+}
+
+/**
+ * Key of an encrypted public File, exported for one recipient, with the File's name, size, type and data hash.
+ * Sent through the delivery zome in the kind of an empty manifest: "<SEALED_FILE_KEY_TYPE_NAME>::<base64>"
+ */
+export interface SealedFileKey {
+  manifest_eh: EntryHash
+  sealed_key: unknown
+  data_hash: string
+  name: string
+  size: number
+  filetype: string
+}
+
+export const INLINE_MARKER = "inline";
+
+/** Max size of a serialized inline File description, so it fits in a link tag (1000 bytes) */
+export const MAX_INLINE_DESCRIPTION_SIZE = 1000;
+
+export const ENCRYPTED_MARKER = "encrypted";
+
+/**
+ * Entry representing a Private Encryption Key of an Agent.
+ * Keys are rotated by creating a new PrivEncKey with a higher id.
+ */
 export interface PrivEncKey {
   value: unknown
+  /** Keys created before rotation existed have id 0 */
+  id: number
+  /** Imported keys keep the id they had on the source-chain they were exported from */
+  is_imported: boolean
+}
+
+/**
+ * Entry recording that one of my private Files has been replaced by a new manifest,
+ * e.g. when it has been renamed or re-encrypted to my newest PrivEncKey.
+ */
+export interface ReplacedFile {
+  previous_eh: EntryHash
+  eh: EntryHash
+}
+
+/**
+ * Entry representing a folder of private Files.
+ * A folder without parent is at the root.
+ */
+export interface PrivateFolder {
+  name: string
+  parent?: EntryHash
+}
+
+/**
+ * Entry representing the placement of a private File in a PrivateFolder.
+ * A File without PrivateFolderItem is at the root.
+ */
+export interface PrivateFolderItem {
+  folder_eh: EntryHash
+  manifest_eh: EntryHash
+}
+
+/** Dna properties */
+export interface FilesProperties {
+  maxChunkSize: number
+  maxParcelSize: number
+  minParcelNameLength: number
+  maxParcelNameLength: number
+  /** Size limit of Files returned whole by get_file_bytes() */
+  maxFileBytesSize: number
+  /** Size limit of Files that can have their content inlined in their manifest */
+  maxInlineFileSize: number
+}
+
+/**
+ * LinkTag of a PublicFolderFile link.
+ * Holds the folder path so validation can check that the link's base is this folder.
+ */
+export interface PublicFolderTag {
+  path: string
+}
+
+/**
+ * LinkTag of NextVersion & PreviousVersion links.
+ * Holds the previous manifest's ActionHash so validation can check who is making a new version.
+ */
+export interface VersionTag {
+  previous_ah: ActionHash
 }
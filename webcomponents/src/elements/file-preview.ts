@@ -49,7 +49,7 @@ export class FilePreview extends DnaElement<FilesDvmPerspective, FilesDvm> {
             if (this._manifest && this._manifest.description.size < this._dvm.dnaProperties.maxChunkSize) {
                 const mime = kind2mime(this._manifest.description.kind_info);
                 //const fileType = kind2Type(this._manifest.description.kind_info);
-                const data = await this._dvm.deliveryZvm.getParcelData(this.hash);
+                const data = await this._dvm.filesZvm.getFileData(this.hash, this._manifest);
                 this._maybeFile = this._dvm.data2File(this._manifest, data);
 
                 const reader = new FileReader();
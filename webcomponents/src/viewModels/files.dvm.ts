@@ -39,7 +39,7 @@ import {
     FilesNotificationVariantReceptionComplete, FilesNotificationVariantReplyReceived
 } from "./files.perspective";
 import {TaggingZvm} from "./tagging.zvm";
-import {FILES_DEFAULT_ROLE_NAME} from "../bindings/files.types";
+import {FILES_DEFAULT_ROLE_NAME, FilesProperties} from "../bindings/files.types";
 import {NotificationsZvm} from "@ddd-qc/notifications-dvm/dist/viewModels/notifications.zvm";
 import {ProfilesZvm} from "@ddd-qc/profiles-dvm";
 
@@ -108,9 +108,9 @@ export class FilesDvm extends DnaViewModel {
 
 
     /** */
-    get dnaProperties(): DeliveryProperties {
+    get dnaProperties(): DeliveryProperties & FilesProperties {
         console.log('dnaProperties() dnaModifiers', this.cell.dnaModifiers);
-        const properties = decode(this.cell.dnaModifiers.properties as Uint8Array) as DeliveryProperties;
+        const properties = decode(this.cell.dnaModifiers.properties as Uint8Array) as DeliveryProperties & FilesProperties;
         console.log('dnaProperties() properties', properties);
         return properties;
     }
@@ -420,6 +420,15 @@ export class FilesDvm extends DnaViewModel {
             }
             return;
         }
+        /** Check if file already stored privately, before writing any chunk */
+        const maybeFound = await this.filesZvm.zomeProxy.findFileByDataHash(splitObj.dataHash);
+        if (maybeFound && "Private" in maybeFound[1].description.visibility) {
+            console.warn("File already stored privately");
+            if (this._mustSendTo) {
+                this._sendFile(encodeHashToBase64(maybeFound[0]), maybeFound[1]);
+            }
+            return;
+        }
         this._perspective.uploadState = {
             splitObj,
             file,
@@ -430,8 +439,13 @@ export class FilesDvm extends DnaViewModel {
         };
         this.notifySubscribers();
 
-        /** Initiate write chunk loop */
-        /* await */ this.writeChunks();
+        /** Tiny Files are inlined in their manifest, otherwise initiate write chunk loop */
+        const maybeContent = this.getInlineContent(file, splitObj);
+        if (maybeContent !== undefined) {
+            /* await */ this.filesZvm.commitPrivateManifest(file, splitObj.dataHash, [], maybeContent);
+        } else {
+            /* await */ this.writeChunks();
+        }
         this._mustAddTags = {isPrivate: true, tags};
         /* Done */
         return splitObj;
@@ -459,6 +473,15 @@ export class FilesDvm extends DnaViewModel {
             }
             return;
         }
+        /** Check if I already published this file, before writing any chunk */
+        const maybeFound = await this.filesZvm.zomeProxy.findFileByDataHash(splitObj.dataHash);
+        if (maybeFound && maybeFound[2] && "Public" in maybeFound[1].description.visibility) {
+            console.warn("File already published");
+            if (callback) {
+                callback(encodeHashToBase64(maybeFound[0]));
+            }
+            return;
+        }
         this._perspective.uploadState = {
             splitObj,
             file,
@@ -470,8 +493,13 @@ export class FilesDvm extends DnaViewModel {
         };
         this.notifySubscribers();
 
-        /** Initial write chunk loop */
-        /*await */ this.writeChunks();
+        /** Tiny Files are inlined in their manifest, otherwise initiate write chunk loop */
+        const maybeContent = this.getInlineContent(file, splitObj);
+        if (maybeContent !== undefined) {
+            /* await */ this.filesZvm.publishFileManifest(file, splitObj.dataHash, [], maybeContent);
+        } else {
+            /*await */ this.writeChunks();
+        }
         // this.filesZvm.zomeProxy.writePublicFileChunks([{data_hash: splitObj.dataHash, data: splitObj.chunks[0]}]);
         this._mustAddTags = {isPrivate: false, tags};
         /** Done */
@@ -479,6 +507,22 @@ export class FilesDvm extends DnaViewModel {
     }
 
 
+    /**
+     * Return the base64 content of a File if it is small enough to be inlined in its manifest.
+     * Its description must fit in a link tag (1000 bytes), so the name and type must be short too.
+     */
+    getInlineContent(file: File, splitObj: SplitObject): string | undefined {
+        if (file.size > this.dnaProperties.maxInlineFileSize || splitObj.numChunks != 1) {
+            return undefined;
+        }
+        const MAX_NAME_AND_TYPE_SIZE = 200;
+        if (new TextEncoder().encode(file.name + file.type).length > MAX_NAME_AND_TYPE_SIZE) {
+            return undefined;
+        }
+        return splitObj.chunks[0];
+    }
+
+
     /** */
     async writeChunks(): Promise<void> {
         const MAX_WEBSOCKET_PAYLOAD = 8 * 1024 * 1024;
@@ -530,7 +574,7 @@ export class FilesDvm extends DnaViewModel {
     async getFile(ppEh: EntryHashB64): Promise<[ParcelManifest, string]> {
         const [manifest, ts] = await this.deliveryZvm.getManifest(ppEh);
         //this.deliveryZvm.perspective.chunkCounts[manifest.data_hash] = 0;
-        const dataB64 = await this.deliveryZvm.getParcelData(ppEh);
+        const dataB64 = await this.filesZvm.getFileData(ppEh, manifest);
         return [manifest, dataB64];
     }
 
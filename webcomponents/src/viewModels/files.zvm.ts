@@ -8,6 +8,7 @@ import {
 import {ZomeViewModel} from "@ddd-qc/lit-happ";
 import {FilesProxy} from "../bindings/files.proxy";
 import {SendFileInput} from "../bindings/files.types";
+import {ParcelManifest} from "@ddd-qc/delivery";
 
 //import WebWorker from 'web-worker:./commitPrivateFile.ts';
 
@@ -67,14 +68,26 @@ export class FilesZvm extends ZomeViewModel {
     /** -- Methods -- */
 
 
-    /** */
-    async commitPrivateManifest(file: File, dataHash: string, chunks: EntryHash[]): Promise<EntryHashB64> {
+    /** Read the base64 content of a File through the files zome, which handles inline Files and encrypted chunks */
+    async getFileData(manifestEh: EntryHashB64, manifest: ParcelManifest): Promise<string> {
+        const count = Math.max(1, manifest.chunks.length);
+        let dataB64 = "";
+        for (let index = 0; index < count; index += 1) {
+            dataB64 += await this.zomeProxy.readFileChunk({manifest_eh: decodeHashFromBase64(manifestEh), index});
+        }
+        return dataB64;
+    }
+
+
+    /** Content must be given instead of chunks for tiny Files, so it is inlined in the manifest */
+    async commitPrivateManifest(file: File, dataHash: string, chunks: EntryHash[], content?: string): Promise<EntryHashB64> {
         const params = {
             filename: file.name,
             filetype: file.type,
             data_hash: dataHash,
             orig_filesize: file.size,
             chunks,
+            content,
         }
         const [manifest_eh, _description] =  await this.zomeProxy.commitPrivateFile(params);
         const ehb64 = encodeHashToBase64(manifest_eh);
@@ -85,13 +98,14 @@ export class FilesZvm extends ZomeViewModel {
 
 
     /** */
-    async publishFileManifest(file: File, dataHash: string, chunks: EntryHash[]): Promise<EntryHashB64> {
+    async publishFileManifest(file: File, dataHash: string, chunks: EntryHash[], content?: string): Promise<EntryHashB64> {
         const params = {
             filename: file.name,
             filetype: file.type,
             data_hash: dataHash,
             orig_filesize: file.size,
             chunks,
+            content,
         }
         const [manifest_eh, _description] =  await this.zomeProxy.publishFileManifest(params);
         const ehb64 = encodeHashToBase64(manifest_eh);